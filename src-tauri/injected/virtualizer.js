@@ -439,6 +439,18 @@
       }
     });
   })();
+  (function() {
+    const g = window;
+    if (g.__online_bound) return;
+    const invoke = g.__TAURI__?.core?.invoke;
+    if (typeof invoke !== "function") return;
+    g.__online_bound = true;
+    const report = () => invoke("set_online", { online: navigator.onLine }).catch(() => {
+    });
+    window.addEventListener("online", report);
+    window.addEventListener("offline", report);
+    report();
+  })();
 })();
 //Download Image Hook
 // === ChatGPT image download: iframe + shadow-root hardened (v5) =============
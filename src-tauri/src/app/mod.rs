@@ -1,6 +0,0 @@
-pub mod cmd;
-pub mod fs_extra;
-pub mod menu;
-pub mod setup;
-pub mod window;
-pub mod control;
\ No newline at end of file
@@ -0,0 +1,417 @@
+// src-tauri/src/archive.rs  — local archive of conversations imported from ChatGPT's data-export .zip
+
+use std::{
+  collections::{HashMap, HashSet},
+  fs,
+  io::Read,
+  path::{Path, PathBuf},
+  sync::Mutex,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use zip::ZipArchive;
+
+use crate::conf::now_ms;
+use crate::storage;
+use crate::utils::{chat_root, create_file};
+
+pub const ARCHIVE_JSON: &str = "chat.archive.json";
+/// `conversations/<id>.json` and `files/<name>` live under this directory.
+pub const ARCHIVE_DIR: &str = "archive";
+const CONVERSATIONS_JSON: &str = "conversations.json";
+/// Failure messages kept in `ImportSummary::errors`; the rest are only counted.
+const MAX_ERRORS: usize = 20;
+
+/// One import at a time, so two runs never interleave their index writes.
+static IMPORT_LOCK: Mutex<()> = Mutex::new(());
+
+// ---------- Export format ----------
+// Only the fields we keep; everything else in conversations.json is ignored.
+#[derive(Debug, Deserialize)]
+struct RawConversation {
+  #[serde(default)]
+  id: Option<String>,
+  #[serde(default)]
+  conversation_id: Option<String>,
+  #[serde(default)]
+  title: Option<String>,
+  #[serde(default)]
+  create_time: Option<f64>,
+  #[serde(default)]
+  update_time: Option<f64>,
+  #[serde(default)]
+  mapping: HashMap<String, RawNode>,
+  #[serde(default)]
+  current_node: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawNode {
+  #[serde(default)]
+  message: Option<RawMessage>,
+  #[serde(default)]
+  parent: Option<String>,
+}
+
+// Nodes vary across export versions; a message without an author is skipped
+// rather than failing the whole file.
+#[derive(Debug, Deserialize)]
+struct RawMessage {
+  #[serde(default)]
+  id: String,
+  #[serde(default)]
+  author: Option<RawAuthor>,
+  #[serde(default)]
+  create_time: Option<f64>,
+  #[serde(default)]
+  content: Value,
+  #[serde(default)]
+  metadata: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawAuthor {
+  #[serde(default)]
+  role: String,
+  #[serde(default)]
+  name: Option<String>,
+}
+
+// ---------- Archive format ----------
+/// One row of `chat.archive.json`. Times are seconds, as in the export.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveEntry {
+  pub id: String,
+  pub title: String,
+  pub create_time: f64,
+  pub update_time: f64,
+  pub messages: usize,
+  pub imported_at: u64,
+}
+
+/// `archive/conversations/<id>.json`: the current branch, root first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedConversation {
+  pub id: String,
+  pub title: String,
+  pub create_time: f64,
+  pub update_time: f64,
+  pub messages: Vec<ArchivedMessage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedMessage {
+  pub id: String,
+  pub role: String,
+  /// Tool or plugin name for `tool` messages.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub name: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub create_time: Option<f64>,
+  pub content_type: String,
+  pub text: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub model: Option<String>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub attachments: Vec<Attachment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attachment {
+  /// `file-…` / `file_…` id used by the export.
+  pub id: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub name: Option<String>,
+  /// Relative to `chat_root()`; `None` when the export did not include the file.
+  pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportSummary {
+  pub added: usize,
+  pub updated: usize,
+  /// Already archived with the same or a newer `update_time`.
+  pub unchanged: usize,
+  /// Without a usable id or any visible message.
+  pub skipped: usize,
+  /// Not archived because of an error; retried by the next import.
+  pub failed: usize,
+  /// `<conversation id>: <error>` for the first failures.
+  pub errors: Vec<String>,
+  pub attachments: usize,
+  pub missing_attachments: usize,
+}
+
+// ---------- Linearising ----------
+/// Messages from the root down to `current_node`, i.e. the branch shown in
+/// the web UI; edits and regenerations on other branches are dropped.
+fn current_branch(conv: &RawConversation) -> Vec<(&str, &RawMessage)> {
+  let leaf = conv.current_node.clone().or_else(|| {
+    // very old exports have no current_node: take the newest message
+    conv
+      .mapping
+      .iter()
+      .filter_map(|(id, node)| Some((id, node.message.as_ref()?.create_time.unwrap_or(0.0))))
+      .max_by(|a, b| a.1.total_cmp(&b.1))
+      .map(|(id, _)| id.clone())
+  });
+
+  let mut branch = vec![];
+  let mut seen = HashSet::new();
+  let mut next = leaf;
+  while let Some(id) = next {
+    // a malformed mapping must not loop forever
+    if !seen.insert(id.clone()) {
+      break;
+    }
+    let Some((key, node)) = conv.mapping.get_key_value(&id) else { break };
+    if let Some(message) = &node.message {
+      branch.push((key.as_str(), message));
+    }
+    next = node.parent.clone();
+  }
+  branch.reverse();
+  branch
+}
+
+fn is_hidden(message: &RawMessage) -> bool {
+  message.metadata.get("is_visually_hidden_from_conversation").and_then(Value::as_bool) == Some(true)
+}
+
+/// Text of any content type: `parts` for text and multimodal, `text` for
+/// code and quotes, `result` for tool output.
+fn content_text(content: &Value) -> String {
+  if let Some(parts) = content.get("parts").and_then(Value::as_array) {
+    return parts.iter().filter_map(Value::as_str).filter(|s| !s.is_empty()).collect::<Vec<_>>().join("\n\n");
+  }
+  match content.get("content_type").and_then(Value::as_str) {
+    Some("code") => {
+      let lang = content.get("language").and_then(Value::as_str).filter(|l| *l != "unknown").unwrap_or("");
+      let code = content.get("text").and_then(Value::as_str).unwrap_or("");
+      if code.is_empty() { String::new() } else { format!("```{lang}\n{code}\n```") }
+    }
+    _ => ["text", "result"]
+      .iter()
+      .find_map(|k| content.get(*k).and_then(Value::as_str))
+      .unwrap_or("")
+      .to_string(),
+  }
+}
+
+/// File ids referenced by image parts (`file-service://file-…`,
+/// `sediment://file_…`) and by uploads in `metadata.attachments`.
+fn attachment_refs(message: &RawMessage) -> Vec<(String, Option<String>)> {
+  let mut refs: Vec<(String, Option<String>)> = vec![];
+  let parts = message.content.get("parts").and_then(Value::as_array).into_iter().flatten();
+  for pointer in parts.filter_map(|p| p.get("asset_pointer")?.as_str()) {
+    if let Some((_, id)) = pointer.split_once("://") {
+      refs.push((id.to_string(), None));
+    }
+  }
+  let uploads = message.metadata.get("attachments").and_then(Value::as_array).into_iter().flatten();
+  for upload in uploads {
+    let Some(id) = upload.get("id").and_then(Value::as_str) else { continue };
+    let name = upload.get("name").and_then(Value::as_str).map(str::to_string);
+    match refs.iter_mut().find(|(i, _)| i == id) {
+      Some(r) => r.1 = name,
+      None => refs.push((id.to_string(), name)),
+    }
+  }
+  refs
+}
+
+/// Ids become file names, so only the characters the export itself uses.
+fn is_safe_id(id: &str) -> bool {
+  !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+// ---------- Reading the export ----------
+struct Export {
+  zip: ZipArchive<fs::File>,
+  /// file name -> index of the zip entry, for attachments
+  files: HashMap<String, usize>,
+}
+
+impl Export {
+  fn open(path: &Path) -> anyhow::Result<(Self, Vec<RawConversation>)> {
+    let mut zip = ZipArchive::new(fs::File::open(path)?)?;
+    let mut files = HashMap::new();
+    let mut conversations = None;
+
+    for i in 0..zip.len() {
+      let entry = zip.by_index(i)?;
+      let Some(name) = entry.enclosed_name() else { continue };
+      if entry.is_dir() {
+        continue;
+      }
+      let Some(file_name) = name.file_name().map(|n| n.to_string_lossy().to_string()) else { continue };
+      // the shallowest conversations.json, in case the zip was re-packed inside a folder
+      let depth = name.components().count();
+      if file_name == CONVERSATIONS_JSON && conversations.is_none_or(|(_, d)| depth < d) {
+        conversations = Some((i, depth));
+      } else {
+        files.entry(file_name).or_insert(i);
+      }
+    }
+
+    let (index, _) = conversations.ok_or_else(|| anyhow::anyhow!("not a ChatGPT data export: {CONVERSATIONS_JSON} is missing"))?;
+    let raw: Vec<RawConversation> = serde_json::from_reader(zip.by_index(index)?)?;
+    Ok((Self { zip, files }, raw))
+  }
+
+  /// Entry named `<id>` or `<id>-<original name>`; the export puts uploads
+  /// at the top level and generated images in sub-folders.
+  fn find(&self, id: &str) -> Option<(&str, usize)> {
+    self
+      .files
+      .iter()
+      .filter(|(name, _)| name.strip_prefix(id).is_some_and(|rest| rest.is_empty() || rest.starts_with(['-', '.'])))
+      .min_by_key(|(name, _)| name.len())
+      .map(|(name, i)| (name.as_str(), *i))
+  }
+
+  /// Copies the file for `id` into `archive/files/` unless it is already there.
+  fn copy(&mut self, root: &Path, id: &str, summary: &mut ImportSummary) -> anyhow::Result<Option<String>> {
+    let Some((name, index)) = self.find(id).map(|(n, i)| (n.to_string(), i)) else {
+      summary.missing_attachments += 1;
+      return Ok(None);
+    };
+    let rel = format!("{ARCHIVE_DIR}/files/{name}");
+    let target = storage::resolve(root, &rel)?;
+    if !target.exists() {
+      let mut bytes = vec![];
+      self.zip.by_index(index)?.read_to_end(&mut bytes)?;
+      create_file(&target, bytes)?;
+      summary.attachments += 1;
+    }
+    Ok(Some(rel))
+  }
+}
+
+// ---------- Import ----------
+fn read_index(root: &Path) -> Vec<ArchiveEntry> {
+  fs::read(root.join(ARCHIVE_JSON))
+    .ok()
+    .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+    .unwrap_or_default()
+}
+
+fn archive(export: &mut Export, root: &Path, raw: &RawConversation, summary: &mut ImportSummary) -> anyhow::Result<Option<ArchivedConversation>> {
+  let Some(id) = raw.conversation_id.as_ref().or(raw.id.as_ref()).filter(|id| is_safe_id(id)) else { return Ok(None) };
+
+  let mut messages = vec![];
+  for (node, message) in current_branch(raw) {
+    let Some(author) = message.author.as_ref().filter(|a| !a.role.is_empty()) else { continue };
+    if is_hidden(message) {
+      continue;
+    }
+    let mut attachments = vec![];
+    for (file_id, name) in attachment_refs(message) {
+      let path = export.copy(root, &file_id, summary)?;
+      attachments.push(Attachment { id: file_id, name, path });
+    }
+    let text = content_text(&message.content);
+    if text.trim().is_empty() && attachments.is_empty() {
+      continue;
+    }
+    messages.push(ArchivedMessage {
+      id: if message.id.is_empty() { node.to_string() } else { message.id.clone() },
+      role: author.role.clone(),
+      name: author.name.clone(),
+      create_time: message.create_time,
+      content_type: message.content.get("content_type").and_then(Value::as_str).unwrap_or("text").into(),
+      text,
+      model: message.metadata.get("model_slug").and_then(Value::as_str).map(str::to_string),
+      attachments,
+    });
+  }
+  if messages.is_empty() {
+    return Ok(None);
+  }
+
+  let create_time = raw.create_time.or_else(|| messages.first()?.create_time).unwrap_or(0.0);
+  Ok(Some(ArchivedConversation {
+    id: id.clone(),
+    title: raw.title.clone().filter(|t| !t.trim().is_empty()).unwrap_or_else(|| "Untitled".into()),
+    create_time,
+    update_time: raw.update_time.unwrap_or(create_time),
+    messages,
+  }))
+}
+
+/// Conversations already archived are rewritten only when the export has a
+/// newer `update_time`; ones missing from the export are kept. A conversation
+/// that fails is counted and left out of the index, so the next run retries it.
+fn import(path: &Path) -> anyhow::Result<ImportSummary> {
+  let root = chat_root();
+  let _guard = IMPORT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+  let (mut export, raw) = Export::open(path)?;
+  let mut index: HashMap<String, ArchiveEntry> = read_index(&root).into_iter().map(|e| (e.id.clone(), e)).collect();
+  let mut summary = ImportSummary::default();
+  let imported_at = now_ms();
+
+  for conv in &raw {
+    let id = conv.conversation_id.as_ref().or(conv.id.as_ref());
+    let known = id.and_then(|id| index.get(id));
+    if let Some(known) = known
+      && known.update_time >= conv.update_time.unwrap_or(0.0)
+      && root.join(ARCHIVE_DIR).join("conversations").join(format!("{}.json", known.id)).exists()
+    {
+      summary.unchanged += 1;
+      continue;
+    }
+    let is_new = known.is_none();
+
+    // one broken conversation or attachment must not lose the others
+    let archived = archive(&mut export, &root, conv, &mut summary).and_then(|archived| {
+      let Some(archived) = archived else { return Ok(None) };
+      let rel = format!("{ARCHIVE_DIR}/conversations/{}.json", archived.id);
+      create_file(storage::resolve(&root, &rel)?, serde_json::to_vec_pretty(&archived)?)?;
+      Ok(Some(archived))
+    });
+    let archived = match archived {
+      Ok(Some(archived)) => archived,
+      Ok(None) => {
+        summary.skipped += 1;
+        continue;
+      }
+      Err(e) => {
+        summary.failed += 1;
+        if summary.errors.len() < MAX_ERRORS {
+          summary.errors.push(format!("{}: {e}", id.map_or("?", String::as_str)));
+        }
+        continue;
+      }
+    };
+    index.insert(
+      archived.id.clone(),
+      ArchiveEntry {
+        id: archived.id,
+        title: archived.title,
+        create_time: archived.create_time,
+        update_time: archived.update_time,
+        messages: archived.messages.len(),
+        imported_at,
+      },
+    );
+    if is_new {
+      summary.added += 1;
+    } else {
+      summary.updated += 1;
+    }
+  }
+
+  let mut entries: Vec<ArchiveEntry> = index.into_values().collect();
+  entries.sort_by(|a, b| b.update_time.total_cmp(&a.update_time));
+  create_file(root.join(ARCHIVE_JSON), serde_json::to_vec_pretty(&entries)?)?;
+  Ok(summary)
+}
+
+// ---------- Tauri commands ----------
+/// `path` is the .zip from "Settings → Data controls → Export data"; safe to
+/// run again on each newer export.
+#[tauri::command]
+pub fn import_openai_export(path: PathBuf) -> Result<ImportSummary, String> {
+  import(&path).map_err(|e| e.to_string())
+}
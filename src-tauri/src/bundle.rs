@@ -0,0 +1,385 @@
+// src-tauri/src/bundle.rs  — move a whole setup between machines as one .zip
+
+use std::{
+  collections::HashMap,
+  fs,
+  io::{Cursor, Read, Write},
+  path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::AppHandle;
+use walkdir::WalkDir;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::cmd_index;
+use crate::conf::{migrate, now_ms, ChatConfJson, CONF_FILE, DEFAULT_CHAT_CONF};
+use crate::profile;
+use crate::utils::{chat_root, create_file};
+
+pub const BUNDLE_MANIFEST: &str = "manifest.json";
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// Single files carried by a bundle; all but chat.conf.json live in `chat_root()`.
+const FILES: &[&str] = &[
+  CONF_FILE,
+  "chat.model.json",
+  "chat.model.cmd.json",
+  "chat.prompts.csv",
+  "chat.notes.json",
+  "chat.download.json",
+  "chat.archive.json",
+];
+
+/// Optional directory trees under `chat_root()`.
+const TREES: &[&str] = &["notes", "download", "archive"];
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+  version: u32,
+  app_version: String,
+  profile: String,
+  created_at_ms: u64,
+  entries: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+  #[default]
+  Skip,
+  /// JSON records are matched by `id`/`cmd`/`act`, CSV rows by their first
+  /// column, tree files by path; the bundle wins on a match, local extras stay.
+  Merge,
+  Replace,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BundleSummary {
+  pub path: PathBuf,
+  pub entries: Vec<String>,
+  pub files: usize,
+}
+
+/// One row of the import preview.
+#[derive(Debug, Clone, Serialize)]
+pub struct BundleEntry {
+  pub name: String,
+  /// `file` or `tree`
+  pub kind: &'static str,
+  /// Bytes for a file, number of files for a tree.
+  pub size: u64,
+  pub local_exists: bool,
+  /// Files whose local copy differs and would be overwritten.
+  pub conflicts: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BundlePreview {
+  pub app_version: String,
+  pub profile: String,
+  pub created_at_ms: u64,
+  pub entries: Vec<BundleEntry>,
+}
+
+fn local_path(name: &str) -> PathBuf {
+  if name == CONF_FILE {
+    ChatConfJson::conf_path()
+  } else {
+    chat_root().join(name)
+  }
+}
+
+fn tree_files(dir: &Path) -> Vec<PathBuf> {
+  WalkDir::new(dir)
+    .into_iter()
+    .flatten()
+    .filter(|e| e.file_type().is_file())
+    .filter_map(|e| e.path().strip_prefix(dir).ok().map(Path::to_path_buf))
+    .collect()
+}
+
+/// Zip entry names always use `/`.
+fn entry_name(tree: &str, rel: &Path) -> String {
+  let parts: Vec<_> = rel.components().map(|c| c.as_os_str().to_string_lossy()).collect();
+  format!("{tree}/{}", parts.join("/"))
+}
+
+// ---------- Export ----------
+fn export(app: &AppHandle, path: &Path, include_trees: bool) -> anyhow::Result<BundleSummary> {
+  let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+  let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+  let mut entries = vec![];
+  let mut files = 0;
+
+  for name in FILES {
+    let Ok(bytes) = fs::read(local_path(name)) else { continue };
+    zip.start_file(*name, options)?;
+    zip.write_all(&bytes)?;
+    entries.push(name.to_string());
+    files += 1;
+  }
+
+  if include_trees {
+    for tree in TREES {
+      let dir = chat_root().join(tree);
+      let rels = tree_files(&dir);
+      if rels.is_empty() {
+        continue;
+      }
+      for rel in &rels {
+        zip.start_file(entry_name(tree, rel), options)?;
+        zip.write_all(&fs::read(dir.join(rel))?)?;
+      }
+      entries.push(format!("{tree}/"));
+      files += rels.len();
+    }
+  }
+
+  let manifest = Manifest {
+    version: BUNDLE_VERSION,
+    app_version: app.package_info().version.to_string(),
+    profile: profile::active().to_string(),
+    created_at_ms: now_ms(),
+    entries: entries.clone(),
+  };
+  zip.start_file(BUNDLE_MANIFEST, options)?;
+  zip.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+
+  let bytes = zip.finish()?.into_inner();
+  create_file(path, bytes)?;
+  Ok(BundleSummary { path: path.to_path_buf(), entries, files })
+}
+
+// ---------- Reading a bundle ----------
+struct Bundle {
+  manifest: Manifest,
+  files: HashMap<String, Vec<u8>>,
+  /// tree name -> (relative path, contents)
+  trees: HashMap<String, Vec<(PathBuf, Vec<u8>)>>,
+}
+
+fn open(path: &Path) -> anyhow::Result<Bundle> {
+  let mut zip = ZipArchive::new(fs::File::open(path)?)?;
+  let mut manifest = None;
+  let mut files = HashMap::new();
+  let mut trees: HashMap<String, Vec<(PathBuf, Vec<u8>)>> = HashMap::new();
+
+  for i in 0..zip.len() {
+    let mut entry = zip.by_index(i)?;
+    // refuses absolute paths and `..`, so nothing lands outside chat_root()
+    let Some(name) = entry.enclosed_name() else { continue };
+    if entry.is_dir() {
+      continue;
+    }
+    let mut bytes = vec![];
+    entry.read_to_end(&mut bytes)?;
+
+    let key = name.to_string_lossy().replace('\\', "/");
+    if key == BUNDLE_MANIFEST {
+      manifest = Some(serde_json::from_slice::<Manifest>(&bytes)?);
+    } else if FILES.contains(&key.as_str()) {
+      files.insert(key, bytes);
+    } else if let Some(tree) = TREES.iter().find(|t| name.starts_with(t)) {
+      let rel = name.strip_prefix(tree)?.to_path_buf();
+      trees.entry(tree.to_string()).or_default().push((rel, bytes));
+    }
+  }
+
+  let manifest = manifest.ok_or_else(|| anyhow::anyhow!("not a ChatGPT bundle: {BUNDLE_MANIFEST} is missing"))?;
+  if manifest.version > BUNDLE_VERSION {
+    anyhow::bail!("bundle format {} is newer than this release supports ({BUNDLE_VERSION})", manifest.version);
+  }
+  Ok(Bundle { manifest, files, trees })
+}
+
+fn preview(path: &Path) -> anyhow::Result<BundlePreview> {
+  let bundle = open(path)?;
+  let mut entries = vec![];
+
+  for name in FILES {
+    let Some(bytes) = bundle.files.get(*name) else { continue };
+    let local = fs::read(local_path(name)).ok();
+    entries.push(BundleEntry {
+      name: name.to_string(),
+      kind: "file",
+      size: bytes.len() as u64,
+      local_exists: local.is_some(),
+      conflicts: local.is_some_and(|l| &l != bytes) as usize,
+    });
+  }
+
+  for tree in TREES {
+    let Some(files) = bundle.trees.get(*tree) else { continue };
+    let dir = chat_root().join(tree);
+    let conflicts = files
+      .iter()
+      .filter(|(rel, bytes)| fs::read(dir.join(rel)).is_ok_and(|l| &l != bytes))
+      .count();
+    entries.push(BundleEntry {
+      name: format!("{tree}/"),
+      kind: "tree",
+      size: files.len() as u64,
+      local_exists: dir.exists(),
+      conflicts,
+    });
+  }
+
+  let Manifest { app_version, profile, created_at_ms, .. } = bundle.manifest;
+  Ok(BundlePreview { app_version, profile, created_at_ms, entries })
+}
+
+// ---------- Import ----------
+/// Everything is computed in memory first and the config, the step most
+/// likely to be refused (policy locks, validation, conflicts), is applied
+/// before any other file is touched; a failure leaves the local setup as it was.
+fn import(path: &Path, modes: &HashMap<String, ImportMode>) -> anyhow::Result<Vec<String>> {
+  let bundle = open(path)?;
+  let mode = |name: &str| modes.get(name).copied().unwrap_or_default();
+  let mut applied = vec![];
+  let mut writes: Vec<(PathBuf, Vec<u8>)> = vec![];
+  let mut conf = None;
+
+  for (name, incoming) in &bundle.files {
+    let mode = mode(name);
+    if mode == ImportMode::Skip {
+      continue;
+    }
+    if name == CONF_FILE {
+      conf = Some((incoming, mode));
+    } else {
+      let target = local_path(name);
+      let bytes = match (mode, fs::read(&target)) {
+        (ImportMode::Merge, Ok(local)) if name.ends_with(".csv") => merge_csv(&local, incoming)?,
+        (ImportMode::Merge, Ok(local)) => {
+          let merged = merge_json(serde_json::from_slice(&local)?, serde_json::from_slice(incoming)?);
+          serde_json::to_vec_pretty(&merged)?
+        }
+        _ => incoming.clone(),
+      };
+      writes.push((target, bytes));
+    }
+    applied.push(name.clone());
+  }
+
+  let mut replaced = vec![];
+  for (tree, files) in &bundle.trees {
+    let name = format!("{tree}/");
+    let mode = mode(&name);
+    if mode == ImportMode::Skip {
+      continue;
+    }
+    let dir = chat_root().join(tree);
+    if mode == ImportMode::Replace && dir.exists() {
+      replaced.push(dir.clone());
+    }
+    writes.extend(files.iter().map(|(rel, bytes)| (dir.join(rel), bytes.clone())));
+    applied.push(name);
+  }
+
+  if let Some((incoming, mode)) = conf {
+    import_conf(incoming, mode)?;
+  }
+  for dir in replaced {
+    fs::remove_dir_all(dir)?;
+  }
+  for (target, bytes) in writes {
+    create_file(target, bytes)?;
+  }
+
+  // the palette and search read the command index, not chat.model.json
+  if let Err(e) = cmd_index::rebuild() {
+    eprintln!("Failed to rebuild chat.model.cmd.json: {e}");
+  }
+  Ok(applied)
+}
+
+/// Goes through `patch_chat_conf` so validation, policy locks and backups
+/// apply; a bundle from an older release is migrated first.
+fn import_conf(incoming: &[u8], mode: ImportMode) -> anyhow::Result<()> {
+  let mut incoming: Value = serde_json::from_slice(incoming)?;
+  migrate(&mut incoming)?;
+  let patch = match mode {
+    // fields missing from the bundle fall back to their defaults
+    ImportMode::Replace => merge_json(serde_json::from_str(DEFAULT_CHAT_CONF)?, incoming),
+    _ => incoming,
+  };
+  ChatConfJson::patch(&patch, None)?;
+  Ok(())
+}
+
+fn record_key(v: &Value) -> Option<(&'static str, &Value)> {
+  ["id", "cmd", "act"].into_iter().find_map(|k| v.get(k).map(|x| (k, x)))
+}
+
+/// Objects merge key by key; arrays of records are matched by `record_key`.
+fn merge_json(local: Value, incoming: Value) -> Value {
+  match (local, incoming) {
+    (Value::Object(mut local), Value::Object(incoming)) => {
+      for (k, v) in incoming {
+        let merged = match local.remove(&k) {
+          Some(l) => merge_json(l, v),
+          None => v,
+        };
+        local.insert(k, merged);
+      }
+      Value::Object(local)
+    }
+    (Value::Array(mut local), Value::Array(incoming)) => {
+      for item in incoming {
+        let pos = record_key(&item).and_then(|key| local.iter().position(|l| record_key(l) == Some(key)));
+        match pos {
+          Some(i) => local[i] = item,
+          None if !local.contains(&item) => local.push(item),
+          None => {}
+        }
+      }
+      Value::Array(local)
+    }
+    (_, incoming) => incoming,
+  }
+}
+
+/// Rows are matched by their first column (`act` in prompts.csv).
+fn merge_csv(local: &[u8], incoming: &[u8]) -> anyhow::Result<Vec<u8>> {
+  let read = |bytes: &[u8]| -> anyhow::Result<(csv::StringRecord, Vec<csv::StringRecord>)> {
+    let mut rdr = csv::ReaderBuilder::new().flexible(true).from_reader(bytes);
+    let headers = rdr.headers()?.clone();
+    let rows = rdr.records().collect::<Result<Vec<_>, _>>()?;
+    Ok((headers, rows))
+  };
+  let (headers, mut rows) = read(local)?;
+  let (incoming_headers, incoming) = read(incoming)?;
+
+  for row in incoming {
+    match rows.iter().position(|r| r.get(0) == row.get(0)) {
+      Some(i) => rows[i] = row,
+      None => rows.push(row),
+    }
+  }
+
+  let mut wtr = csv::WriterBuilder::new().flexible(true).from_writer(vec![]);
+  wtr.write_record(if headers.is_empty() { &incoming_headers } else { &headers })?;
+  for row in &rows {
+    wtr.write_record(row)?;
+  }
+  Ok(wtr.into_inner()?)
+}
+
+// ---------- Tauri commands ----------
+#[tauri::command]
+pub fn export_bundle(app: AppHandle, path: PathBuf, include_trees: bool) -> Result<BundleSummary, String> {
+  export(&app, &path, include_trees).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn preview_bundle(path: PathBuf) -> Result<BundlePreview, String> {
+  preview(&path).map_err(|e| e.to_string())
+}
+
+/// `modes` maps entry names from the preview to a mode; unlisted entries are skipped.
+#[tauri::command]
+pub fn import_bundle(path: PathBuf, modes: HashMap<String, ImportMode>) -> Result<Vec<String>, String> {
+  import(&path, &modes).map_err(|e| e.to_string())
+}
@@ -0,0 +1,180 @@
+// src-tauri/src/cmd_index.rs  — merged slash-command index (chat.model.cmd.json) read by cmd.js
+
+use std::{collections::HashMap, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::conf::{now_ms, ChatConfJson, CmdPrecedence, CmdSource};
+use crate::prompts::{self, gen_cmd, PromptRecord, SYNC_PROMPTS_ID};
+use crate::search;
+use crate::sources;
+use crate::template::{self, TemplateVar};
+use crate::utils::{chat_root, create_file};
+
+pub const CHAT_MODEL_CMD_JSON: &str = "chat.model.cmd.json";
+/// Record list of the UserCustom view in `cache_model/`.
+pub const USER_CUSTOM_ID: &str = "user_custom";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CmdEntry {
+  #[serde(flatten)]
+  pub record: PromptRecord,
+  pub source: CmdSource,
+  /// `cache_model/<source_id>.json` the command was read from.
+  pub source_id: String,
+  pub source_name: String,
+  /// Original name when it was not a valid command and had to be slugified.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub renamed_from: Option<String>,
+  /// `{{variables}}` cmd.js asks for before inserting the prompt.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub vars: Vec<TemplateVar>,
+  /// Set when the prompt is not a valid template; it is then inserted as is.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub template_error: Option<String>,
+}
+
+/// A command defined by more than one source; `kept` won by precedence.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CmdConflict {
+  pub cmd: String,
+  pub kept: String,
+  pub dropped: Vec<String>,
+}
+
+/// A command defined more than once within one source; acts in file order,
+/// the first is kept.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CmdDuplicate {
+  pub cmd: String,
+  pub source: String,
+  pub kept: String,
+  pub dropped: Vec<String>,
+}
+
+/// Shape of `chat.model.cmd.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CmdIndex {
+  pub name: String,
+  pub last_updated: u64,
+  pub data: Vec<CmdEntry>,
+  #[serde(default)]
+  pub conflicts: Vec<CmdConflict>,
+  #[serde(default)]
+  pub duplicates: Vec<CmdDuplicate>,
+}
+
+/// Every record list that feeds the index, as (source, id, display name).
+fn inputs() -> Vec<(CmdSource, String, String)> {
+  let mut inputs = vec![(CmdSource::User, USER_CUSTOM_ID.to_string(), "User Custom".to_string())];
+  for source in sources::list() {
+    let name = if source.name.is_empty() { source.id.clone() } else { source.name.clone() };
+    inputs.push((CmdSource::Custom, source.id, name));
+  }
+  inputs.push((CmdSource::Awesome, SYNC_PROMPTS_ID.to_string(), "awesome-chatgpt-prompts".to_string()));
+  inputs
+}
+
+fn template_fields(prompt: &str) -> (Vec<TemplateVar>, Option<String>) {
+  match template::parse(prompt) {
+    Ok(t) => (t.vars, None),
+    Err(e) => (vec![], Some(e.to_string())),
+  }
+}
+
+/// Merges all enabled records. Sources are visited in precedence order (file
+/// order within a source), so the first definition of a command wins.
+pub fn build(root: &Path, precedence: &CmdPrecedence) -> CmdIndex {
+  let mut inputs = inputs();
+  inputs.sort_by_key(|(source, _, _)| precedence.rank(*source));
+
+  let mut data: Vec<CmdEntry> = vec![];
+  let mut by_cmd: HashMap<String, usize> = HashMap::new();
+  let mut conflicts: Vec<CmdConflict> = vec![];
+  let mut duplicates: Vec<CmdDuplicate> = vec![];
+
+  for (source, source_id, source_name) in inputs {
+    for mut record in prompts::read_cache(root, &source_id).into_iter().filter(|r| r.enable) {
+      let raw = record.cmd.trim().to_string();
+      let mut cmd = gen_cmd(&raw);
+      if cmd.is_empty() {
+        cmd = gen_cmd(&record.act);
+      }
+      if cmd.is_empty() {
+        continue;
+      }
+      let renamed_from = (cmd != raw).then_some(raw);
+
+      if let Some(&i) = by_cmd.get(&cmd) {
+        let kept = &data[i];
+        if kept.source_id == source_id {
+          match duplicates.iter_mut().find(|d| d.cmd == cmd && d.source == source_name) {
+            Some(d) => d.dropped.push(record.act),
+            None => duplicates.push(CmdDuplicate {
+              cmd,
+              source: source_name.clone(),
+              kept: kept.record.act.clone(),
+              dropped: vec![record.act],
+            }),
+          }
+        } else {
+          match conflicts.iter_mut().find(|c| c.cmd == cmd) {
+            Some(c) if !c.dropped.contains(&source_name) => c.dropped.push(source_name.clone()),
+            Some(_) => {}
+            None => conflicts.push(CmdConflict { cmd, kept: kept.source_name.clone(), dropped: vec![source_name.clone()] }),
+          }
+        }
+        continue;
+      }
+
+      let (vars, template_error) = template_fields(&record.prompt);
+      record.cmd = cmd.clone();
+      by_cmd.insert(cmd, data.len());
+      data.push(CmdEntry {
+        record,
+        source,
+        source_id: source_id.clone(),
+        source_name: source_name.clone(),
+        renamed_from,
+        vars,
+        template_error,
+      });
+    }
+  }
+
+  CmdIndex { name: "ChatGPT CMD".into(), last_updated: now_ms(), data, conflicts, duplicates }
+}
+
+/// Rebuilds and writes `chat.model.cmd.json`.
+pub fn rebuild() -> anyhow::Result<CmdIndex> {
+  let root = chat_root();
+  let index = build(&root, &ChatConfJson::load().cmd_precedence);
+  create_file(root.join(CHAT_MODEL_CMD_JSON), serde_json::to_vec_pretty(&index)?)?;
+  search::reindex(&index.data);
+  Ok(index)
+}
+
+// ---------- Tauri commands ----------
+/// Rebuilds the index after a prompt list changed.
+#[tauri::command]
+pub fn cmd_list() -> Result<CmdIndex, String> {
+  rebuild().map_err(|e| e.to_string())
+}
+
+/// The index as last written, built on first use.
+#[tauri::command]
+pub fn get_chat_model_cmd() -> Result<CmdIndex, String> {
+  let written: Option<CmdIndex> = fs::read(chat_root().join(CHAT_MODEL_CMD_JSON))
+    .ok()
+    .and_then(|bytes| serde_json::from_slice(&bytes).ok());
+  match written {
+    Some(mut index) => {
+      // files written before templates existed have no `vars`
+      for entry in &mut index.data {
+        (entry.vars, entry.template_error) = template_fields(&entry.record.prompt);
+      }
+      Ok(index)
+    }
+    None => cmd_list(),
+  }
+}
@@ -1,11 +1,30 @@
 // src-tauri/src/conf.rs  (Linux-only, Tauri 2.x)
 
-use crate::utils::{chat_root, create_file, exists};
-use serde::{Deserialize, Serialize};
-use std::{fs, path::PathBuf};
+use crate::overrides::{self, EffectiveConf};
+use crate::policy;
+use crate::prompts::GITHUB_PROMPTS_CSV_URL;
+use crate::utils::{chat_config_dir, chat_state_dir, create_file, exists};
+use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
+use serde_json::{Map, Value};
+use std::{
+  fmt,
+  fs,
+  path::{Path, PathBuf},
+  str::FromStr,
+  sync::Mutex,
+  time::{SystemTime, UNIX_EPOCH},
+};
+use tauri::Url;
+use tauri_plugin_global_shortcut::Shortcut;
 
-// ---------- Default config (JSON written when file is missing/corrupt) ----------
+pub const CONF_FILE: &str = "chat.conf.json";
+
+// ---------- Schema version (bump together with a new entry in MIGRATIONS) ----------
+pub const CONF_SCHEMA_VERSION: u32 = 1;
+
+// ---------- Default config (JSON written when file is missing) ----------
 pub const DEFAULT_CHAT_CONF: &str = r#"{
+  "schema_version": 1,
   "theme": "system",
   "auto_update": true,
   "tray": true,
@@ -17,73 +36,678 @@ pub const DEFAULT_CHAT_CONF: &str = r#"{
   "ua_tray": "",
   "global_shortcut": "",
   "titlebar": false,
-  "hide_dock_icon": false
+  "hide_dock_icon": false,
+  "prompts_url": "https://raw.githubusercontent.com/f/awesome-chatgpt-prompts/main/prompts.csv",
+  "cmd_precedence": ["user", "custom", "awesome"],
+  "sync_interval": 0
 }"#;
 
+/// Shortest background sync interval in minutes, to keep prompt hosts from being polled.
+pub const MIN_SYNC_INTERVAL: u64 = 5;
+
+// ---------- Number of good copies kept in the state dir's backups/ ----------
+pub const CONF_BACKUP_LIMIT: usize = 10;
+
+// ---------- Errors surfaced to the UI instead of silently resetting ----------
+#[derive(Debug, thiserror::Error)]
+pub enum ConfError {
+  #[error("cannot read chat.conf.json: {0}")]
+  Io(#[from] std::io::Error),
+  #[error("chat.conf.json is not valid: {0}")]
+  Parse(#[from] serde_json::Error),
+  #[error("chat.conf.json uses schema version {found}, this release only understands up to {supported}")]
+  UnsupportedVersion { found: u32, supported: u32 },
+  #[error("cannot upgrade chat.conf.json from schema version {from}: {reason}")]
+  Migration { from: u32, reason: String },
+  #[error("{}", .0.iter().map(|e| format!("{}: {}", e.field, e.message)).collect::<Vec<_>>().join("; "))]
+  Invalid(Vec<FieldError>),
+  #[error("{}", .0.iter().map(|e| format!("{}: {}", e.field, e.message)).collect::<Vec<_>>().join("; "))]
+  Locked(Vec<FieldError>),
+  #[error("chat.conf.json was changed elsewhere (expected revision {expected}, found {actual}), reload and try again")]
+  Conflict { expected: String, actual: String },
+}
+
+impl ConfError {
+  pub fn kind(&self) -> &'static str {
+    match self {
+      ConfError::Io(_) => "io",
+      ConfError::Parse(_) => "parse",
+      ConfError::UnsupportedVersion { .. } => "unsupported_version",
+      ConfError::Migration { .. } => "migration",
+      ConfError::Invalid(_) => "invalid",
+      ConfError::Locked(_) => "locked",
+      ConfError::Conflict { .. } => "conflict",
+    }
+  }
+}
+
+impl Serialize for ConfError {
+  fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    let fields: &[FieldError] = match self {
+      ConfError::Invalid(fields) | ConfError::Locked(fields) => fields,
+      _ => &[],
+    };
+    let mut s = serializer.serialize_struct("ConfError", 4)?;
+    s.serialize_field("kind", self.kind())?;
+    s.serialize_field("message", &self.to_string())?;
+    s.serialize_field("path", &ChatConfJson::conf_path())?;
+    s.serialize_field("fields", fields)?;
+    s.end()
+  }
+}
+
+// ---------- Migrations: MIGRATIONS[n] upgrades a version `n` file to `n + 1` ----------
+type Migration = fn(&mut Map<String, Value>) -> Result<(), String>;
+
+const MIGRATIONS: &[Migration] = &[migrate_v0_to_v1];
+
+/// v0 covers files written before `schema_version` existed, including the
+/// legacy lencx layout (`"theme": "Dark"`, `"auto_update": "Prompt"`,
+/// `chat.openai.com` origins).
+fn migrate_v0_to_v1(conf: &mut Map<String, Value>) -> Result<(), String> {
+  if let Some(theme) = conf.get("theme").and_then(Value::as_str) {
+    let theme = theme.to_lowercase();
+    let theme = match theme.as_str() {
+      "light" | "dark" => theme,
+      _ => "system".to_string(),
+    };
+    conf.insert("theme".into(), Value::String(theme));
+  }
+
+  if let Some(policy) = conf.get("auto_update").and_then(Value::as_str) {
+    let enabled = !policy.eq_ignore_ascii_case("disable");
+    conf.insert("auto_update".into(), Value::Bool(enabled));
+  }
+
+  for key in ["origin", "default_origin"] {
+    let legacy = conf
+      .get(key)
+      .and_then(Value::as_str)
+      .map(|v| v.trim_end_matches('/') == "https://chat.openai.com")
+      .unwrap_or(false);
+    if legacy {
+      conf.insert(key.into(), Value::String("https://chatgpt.com".into()));
+    }
+  }
+
+  Ok(())
+}
+
+/// Runs every pending migration on `value` in place and returns the version
+/// the file was at before the upgrade.
+pub(crate) fn migrate(value: &mut Value) -> Result<u32, ConfError> {
+  let conf = value.as_object_mut().ok_or(ConfError::Migration {
+    from: 0,
+    reason: "top-level value is not an object".into(),
+  })?;
+
+  let found = match conf.get("schema_version") {
+    None => 0,
+    Some(v) => {
+      let version = v.as_u64().ok_or(ConfError::Migration {
+        from: 0,
+        reason: format!("schema_version {v} is not a number"),
+      })?;
+      // a huge or corrupt value must not wrap around into an old version
+      u32::try_from(version).map_err(|_| ConfError::Migration {
+        from: 0,
+        reason: format!("schema_version {version} is out of range"),
+      })?
+    }
+  };
+  if found > CONF_SCHEMA_VERSION {
+    return Err(ConfError::UnsupportedVersion { found, supported: CONF_SCHEMA_VERSION });
+  }
+
+  for (from, step) in MIGRATIONS.iter().enumerate().skip(found as usize) {
+    step(conf).map_err(|reason| ConfError::Migration { from: from as u32, reason })?;
+    conf.insert("schema_version".into(), Value::from(from as u32 + 1));
+  }
+
+  Ok(found)
+}
+
+// ---------- Typed values ----------
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+  Light,
+  Dark,
+  #[default]
+  System,
+}
+
+impl Theme {
+  pub fn as_str(self) -> &'static str {
+    match self {
+      Theme::Light => "light",
+      Theme::Dark => "dark",
+      Theme::System => "system",
+    }
+  }
+
+  /// `None` lets the window follow the OS.
+  pub fn native(self) -> Option<tauri::Theme> {
+    match self {
+      Theme::Light => Some(tauri::Theme::Light),
+      Theme::Dark => Some(tauri::Theme::Dark),
+      Theme::System => None,
+    }
+  }
+}
+
+/// An absolute http(s) URL, e.g. the core window's origin or a prompt source.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Origin(Url);
+
+impl Origin {
+  pub fn url(&self) -> &Url {
+    &self.0
+  }
+}
+
+impl Default for Origin {
+  fn default() -> Self {
+    Origin(Url::parse("https://chatgpt.com").unwrap())
+  }
+}
+
+impl TryFrom<String> for Origin {
+  type Error = String;
+
+  fn try_from(value: String) -> Result<Self, Self::Error> {
+    let url = Url::parse(value.trim()).map_err(|e| format!("\"{value}\" is not a valid URL ({e})"))?;
+    if !matches!(url.scheme(), "http" | "https") || url.host().is_none() {
+      return Err(format!("\"{value}\" must be an http:// or https:// URL"));
+    }
+    Ok(Origin(url))
+  }
+}
+
+impl From<Origin> for String {
+  fn from(origin: Origin) -> Self {
+    origin.0.to_string()
+  }
+}
+
+/// Where a slash command came from: UserCustom, a SyncCustom source or awesome-chatgpt-prompts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CmdSource {
+  User,
+  Custom,
+  Awesome,
+}
+
+/// Which source wins when two define the same command, highest first. Sources
+/// left out keep their default order after the listed ones.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<CmdSource>", into = "Vec<CmdSource>")]
+pub struct CmdPrecedence(Vec<CmdSource>);
+
+impl CmdPrecedence {
+  const DEFAULT: [CmdSource; 3] = [CmdSource::User, CmdSource::Custom, CmdSource::Awesome];
+
+  /// 0 is the highest precedence.
+  pub fn rank(&self, source: CmdSource) -> usize {
+    self.0.iter().position(|s| *s == source).unwrap_or(self.0.len())
+  }
+}
+
+impl Default for CmdPrecedence {
+  fn default() -> Self {
+    CmdPrecedence(Self::DEFAULT.to_vec())
+  }
+}
+
+impl TryFrom<Vec<CmdSource>> for CmdPrecedence {
+  type Error = String;
+
+  fn try_from(mut value: Vec<CmdSource>) -> Result<Self, Self::Error> {
+    for (i, source) in value.iter().enumerate() {
+      if value[..i].contains(source) {
+        return Err(format!("{} is listed twice", serde_json::to_value(source).unwrap_or_default()));
+      }
+    }
+    for source in Self::DEFAULT {
+      if !value.contains(&source) {
+        value.push(source);
+      }
+    }
+    Ok(CmdPrecedence(value))
+  }
+}
+
+impl From<CmdPrecedence> for Vec<CmdSource> {
+  fn from(precedence: CmdPrecedence) -> Self {
+    precedence.0
+  }
+}
+
+/// A global shortcut such as `CmdOrCtrl+Shift+O`; empty disables it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(try_from = "String", into = "String")]
+pub struct Accelerator(String);
+
+impl Accelerator {
+  pub fn as_str(&self) -> &str {
+    &self.0
+  }
+}
+
+impl TryFrom<String> for Accelerator {
+  type Error = String;
+
+  fn try_from(value: String) -> Result<Self, Self::Error> {
+    let value = value.trim().to_string();
+    if !value.is_empty() {
+      Shortcut::from_str(&value).map_err(|e| format!("\"{value}\" is not a valid shortcut ({e})"))?;
+    }
+    Ok(Accelerator(value))
+  }
+}
+
+impl From<Accelerator> for String {
+  fn from(accelerator: Accelerator) -> Self {
+    accelerator.0
+  }
+}
+
+impl fmt::Display for Accelerator {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(&self.0)
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+  pub field: String,
+  pub message: String,
+}
+
 // ---------- Strongly-typed config ----------
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(default)] // tolerate missing fields in older user files
 pub struct ChatConfJson {
-  pub theme: String,
+  pub schema_version: u32,
+  pub theme: Theme,
   pub auto_update: bool,
   pub tray: bool,
   pub popup_search: bool,
   pub stay_on_top: bool,
-  pub default_origin: String,
-  pub origin: String,
+  pub default_origin: Origin,
+  pub origin: Origin,
   pub ua_window: String,
   pub ua_tray: String,
-  pub global_shortcut: String,
+  pub global_shortcut: Accelerator,
   pub titlebar: bool,
   pub hide_dock_icon: bool,
+  /// CSV fetched by `sync_prompts`.
+  #[serde(default = "default_prompts_url")]
+  pub prompts_url: Origin,
+  /// Order in which duplicate slash commands are resolved.
+  pub cmd_precedence: CmdPrecedence,
+  /// Minutes between background syncs of the remote prompt sources; 0 turns them off.
+  pub sync_interval: u64,
+}
+
+fn default_prompts_url() -> Origin {
+  Origin::try_from(GITHUB_PROMPTS_CSV_URL.to_string()).unwrap_or_default()
 }
 
 impl ChatConfJson {
   pub fn conf_path() -> PathBuf {
-    chat_root().join("chat.conf.json")
+    chat_config_dir().join(CONF_FILE)
   }
 
-  pub fn load() -> Self {
+  pub fn defaults() -> Self {
+    serde_json::from_str(DEFAULT_CHAT_CONF).unwrap_or_default()
+  }
+
+  pub fn backups_dir() -> PathBuf {
+    chat_state_dir().join("backups")
+  }
+
+  /// Reads the config, upgrading older layouts in place. When the file cannot
+  /// be read or parsed, the newest valid backup is restored instead; if none
+  /// exists the file is left untouched and the original error is reported.
+  pub fn try_load() -> Result<Self, ConfError> {
     let path = Self::conf_path();
 
     if !exists(&path) {
-      let _ = create_file(&path, DEFAULT_CHAT_CONF);
-      return serde_json::from_str(DEFAULT_CHAT_CONF).unwrap_or_default();
+      create_file(&path, DEFAULT_CHAT_CONF)?;
+      return Ok(Self::defaults());
     }
 
-    let raw = fs::read_to_string(&path).unwrap_or_else(|_| DEFAULT_CHAT_CONF.to_string());
-    serde_json::from_str(&raw).unwrap_or_else(|_| {
-      let _ = create_file(&path, DEFAULT_CHAT_CONF);
-      serde_json::from_str(DEFAULT_CHAT_CONF).unwrap_or_default()
-    })
+    match Self::read(&path) {
+      Ok((conf, found)) => {
+        if found < CONF_SCHEMA_VERSION {
+          conf.save().map_err(|e| ConfError::Migration { from: found, reason: e.to_string() })?;
+        }
+        Ok(conf)
+      }
+      Err(e @ ConfError::UnsupportedVersion { .. }) => Err(e),
+      Err(e) => Self::recover_from_backup().ok_or(e),
+    }
   }
 
-  pub fn save(&self) -> anyhow::Result<()> {
+  /// Parses and migrates a config file without touching it on disk.
+  pub(crate) fn read(path: &Path) -> Result<(Self, u32), ConfError> {
+    let raw = fs::read_to_string(path)?;
+    let mut value: Value = serde_json::from_str(&raw)?;
+    let found = migrate(&mut value)?;
+    Ok((serde_json::from_value(value)?, found))
+  }
+
+  fn recover_from_backup() -> Option<Self> {
+    let backup = list_backups()
+      .into_iter()
+      .find_map(|b| Self::read(&Self::backups_dir().join(&b.name)).ok().map(|(conf, _)| (b, conf)));
+    let (backup, conf) = backup?;
+
+    // keep the broken file around next to the backups so nothing is lost
+    let corrupt = Self::backups_dir().join(format!("chat.conf.{}.corrupt", now_ms()));
+    let _ = fs::copy(Self::conf_path(), corrupt);
+
+    eprintln!("chat.conf.json is unreadable, restored backup {}", backup.name);
+    conf.save().ok()?;
+    Some(conf)
+  }
+
+  /// Copies the current file into the backups dir (only if it still loads)
+  /// and prunes everything beyond `CONF_BACKUP_LIMIT`.
+  fn backup_current() -> std::io::Result<()> {
     let path = Self::conf_path();
-    let s = serde_json::to_string_pretty(self)?;
+    if Self::read(&path).is_err() {
+      return Ok(());
+    }
+
+    let dir = Self::backups_dir();
+    fs::create_dir_all(&dir)?;
+    fs::copy(&path, dir.join(format!("chat.conf.{}.json", now_ms())))?;
+
+    for stale in list_backups().into_iter().skip(CONF_BACKUP_LIMIT) {
+      let _ = fs::remove_file(dir.join(stale.name));
+    }
+    Ok(())
+  }
+
+  /// The config the app runs with: `try_load` with env and `--set` overrides
+  /// on top. Falls back to in-memory defaults so startup never blocks on a
+  /// broken file; the file itself is not overwritten.
+  pub fn load() -> Self {
+    Self::effective().conf
+  }
+
+  /// Same as `load`, plus where each value came from.
+  pub fn effective() -> EffectiveConf {
+    match Self::try_load() {
+      Ok(conf) => overrides::apply(conf, true),
+      Err(e) => {
+        eprintln!("Failed to load chat.conf.json, using defaults: {e}");
+        overrides::apply(Self::defaults(), false)
+      }
+    }
+  }
+
+  /// Checks a raw config object field by field, so every problem is reported
+  /// at once instead of the first serde error.
+  pub fn validate(value: Value) -> Result<Self, ConfError> {
+    let Value::Object(fields) = &value else {
+      return Err(ConfError::Invalid(vec![FieldError {
+        field: "".into(),
+        message: "config must be a JSON object".into(),
+      }]));
+    };
+
+    let mut errors = vec![];
+    let defaults = serde_json::to_value(Self::defaults()).unwrap_or_default();
+    for (field, v) in fields {
+      if defaults.get(field).is_none() {
+        continue; // unknown keys are dropped on save
+      }
+      // deserialize a one-field object on top of the defaults to type-check just this value
+      let mut probe = defaults.clone();
+      probe[field] = v.clone();
+      if let Err(e) = serde_json::from_value::<Self>(probe) {
+        errors.push(FieldError { field: field.clone(), message: e.to_string() });
+      }
+    }
+
+    // a user agent ends up in an HTTP header, so it has to stay on one line
+    for field in ["ua_window", "ua_tray"] {
+      let ua = fields.get(field).and_then(Value::as_str).unwrap_or_default();
+      if ua.chars().any(char::is_control) {
+        errors.push(FieldError {
+          field: field.into(),
+          message: "must be a single line without control characters".into(),
+        });
+      }
+    }
+
+    if let Some(minutes) = fields.get("sync_interval").and_then(Value::as_u64)
+      && minutes > 0
+      && minutes < MIN_SYNC_INTERVAL
+    {
+      errors.push(FieldError {
+        field: "sync_interval".into(),
+        message: format!("must be 0 (off) or at least {MIN_SYNC_INTERVAL} minutes"),
+      });
+    }
+
+    if !errors.is_empty() {
+      return Err(ConfError::Invalid(errors));
+    }
+    Ok(serde_json::from_value(value)?)
+  }
+
+  /// Content hash of the file on disk, so a save from any window or an
+  /// external editor yields a new revision.
+  pub fn revision() -> String {
+    fs::read(Self::conf_path()).map(|bytes| fnv1a(&bytes)).unwrap_or_default()
+  }
+
+  /// Applies an RFC 7386 merge patch and saves the result. When `expected` is
+  /// given, the update is refused if the file changed since that revision.
+  /// Patches that move a policy-locked field are refused as a whole.
+  pub fn patch(patch: &Value, expected: Option<&str>) -> Result<ConfSnapshot, ConfError> {
+    let locked = policy::violations(&policy::load(), patch);
+    if !locked.is_empty() {
+      return Err(ConfError::Locked(locked));
+    }
+    let _guard = CONF_WRITE.lock().unwrap_or_else(|e| e.into_inner());
+
+    let current = Self::try_load()?;
+    let actual = Self::revision();
+    if let Some(expected) = expected
+      && expected != actual
+    {
+      return Err(ConfError::Conflict { expected: expected.into(), actual });
+    }
+
+    let mut value = serde_json::to_value(&current)?;
+    merge_patch(&mut value, patch);
+    let conf = Self::validate(value)?;
+    conf.save().map_err(|e| ConfError::Io(std::io::Error::other(e.to_string())))?;
+    Ok(ConfSnapshot { conf, revision: Self::revision() })
+  }
+
+  /// Low-level write; everything outside the load path goes through `patch`.
+  fn save(&self) -> anyhow::Result<()> {
+    let path = Self::conf_path();
+    if let Err(e) = Self::backup_current() {
+      eprintln!("Failed to back up chat.conf.json: {e}");
+    }
+    let conf = ChatConfJson { schema_version: CONF_SCHEMA_VERSION, ..self.clone() };
+    let s = serde_json::to_string_pretty(&conf)?;
     create_file(&path, &s)?;
     Ok(())
   }
+}
+
+// ---------- Revisions + merge patches ----------
+/// Serializes load-modify-save cycles between windows of this process.
+static CONF_WRITE: Mutex<()> = Mutex::new(());
+
+/// A config together with the revision it was read at.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfSnapshot {
+  #[serde(flatten)]
+  pub conf: ChatConfJson,
+  pub revision: String,
+}
+
+/// 64-bit FNV-1a, hex encoded.
+pub(crate) fn fnv1a(bytes: &[u8]) -> String {
+  let hash = bytes.iter().fold(0xcbf29ce484222325u64, |hash, b| {
+    (hash ^ *b as u64).wrapping_mul(0x100000001b3)
+  });
+  format!("{hash:016x}")
+}
 
-  pub fn reset_to_defaults() -> anyhow::Result<Self> {
-    let conf: ChatConfJson = serde_json::from_str(DEFAULT_CHAT_CONF)?;
-    conf.save()?;
-    Ok(conf)
+/// RFC 7386: objects merge recursively, `null` removes a key (back to its
+/// default), anything else replaces the target.
+fn merge_patch(target: &mut Value, patch: &Value) {
+  let Value::Object(patch) = patch else {
+    *target = patch.clone();
+    return;
+  };
+  if !target.is_object() {
+    *target = Value::Object(Map::new());
   }
+  if let Value::Object(target) = target {
+    for (key, value) in patch {
+      if value.is_null() {
+        target.remove(key);
+      } else {
+        merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+      }
+    }
+  }
+}
+
+// ---------- Backups ----------
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfBackup {
+  pub name: String,
+  pub created_at_ms: u64,
+  pub size: u64,
+}
+
+pub fn now_ms() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_millis() as u64)
+    .unwrap_or_default()
+}
+
+/// Backups named `chat.conf.<ms>.json`, newest first.
+fn list_backups() -> Vec<ConfBackup> {
+  let Ok(entries) = fs::read_dir(ChatConfJson::backups_dir()) else {
+    return vec![];
+  };
+
+  let mut backups: Vec<ConfBackup> = entries
+    .flatten()
+    .filter_map(|entry| {
+      let name = entry.file_name().into_string().ok()?;
+      let created_at_ms = name.strip_prefix("chat.conf.")?.strip_suffix(".json")?.parse().ok()?;
+      let size = entry.metadata().map(|m| m.len()).unwrap_or_default();
+      Some(ConfBackup { name, created_at_ms, size })
+    })
+    .collect();
+  backups.sort_by_key(|b| std::cmp::Reverse(b.created_at_ms));
+  backups
 }
 
 // ---------- Tauri commands ----------
 #[tauri::command]
-pub fn get_chat_conf() -> ChatConfJson {
-  ChatConfJson::load()
+pub fn get_chat_conf() -> Result<ConfSnapshot, ConfError> {
+  let conf = ChatConfJson::try_load()?;
+  Ok(ConfSnapshot { conf, revision: ChatConfJson::revision() })
+}
+
+#[tauri::command]
+pub fn get_chat_conf_path() -> PathBuf {
+  ChatConfJson::conf_path()
+}
+
+/// Full-object save; honours a `revision` key if the caller sends one back.
+#[tauri::command]
+pub fn set_chat_conf(conf: Value) -> Result<(), ConfError> {
+  let expected = conf.get("revision").and_then(Value::as_str).map(str::to_owned);
+  ChatConfJson::patch(&conf, expected.as_deref()).map(|_| ())
+}
+
+#[tauri::command]
+pub fn patch_chat_conf(patch: Value, revision: Option<String>) -> Result<ConfSnapshot, ConfError> {
+  ChatConfJson::patch(&patch, revision.as_deref())
+}
+
+#[tauri::command]
+pub fn reset_chat_conf() -> Result<ConfSnapshot, ConfError> {
+  let mut defaults: Value = serde_json::from_str(DEFAULT_CHAT_CONF)?;
+  // locked fields are left alone rather than failing the whole reset
+  if let Value::Object(fields) = &mut defaults {
+    let policy = policy::load();
+    fields.retain(|field, _| !policy.contains_key(field));
+  }
+  ChatConfJson::patch(&defaults, None)
 }
 
 #[tauri::command]
-pub fn set_chat_conf(conf: ChatConfJson) -> Result<(), String> {
-  conf.save().map_err(|e| e.to_string())
+pub fn list_conf_backups() -> Vec<ConfBackup> {
+  list_backups()
 }
 
 #[tauri::command]
-pub fn reset_chat_conf() -> Result<ChatConfJson, String> {
-  ChatConfJson::reset_to_defaults().map_err(|e| e.to_string())
+pub fn restore_conf_backup(name: String) -> Result<ConfSnapshot, ConfError> {
+  let backup = list_backups().into_iter().find(|b| b.name == name).ok_or_else(|| {
+    ConfError::Io(std::io::Error::new(std::io::ErrorKind::NotFound, format!("no backup named {name}")))
+  })?;
+  let (conf, _) = ChatConfJson::read(&ChatConfJson::backups_dir().join(backup.name))?;
+  ChatConfJson::patch(&serde_json::to_value(conf)?, None)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  #[test]
+  fn migrates_a_legacy_file_to_the_current_version() {
+    let mut conf = json!({ "theme": "Dark", "auto_update": "Prompt", "origin": "https://chat.openai.com/" });
+    assert_eq!(migrate(&mut conf).unwrap(), 0);
+    assert_eq!(conf["schema_version"], json!(CONF_SCHEMA_VERSION));
+    assert_eq!(conf["theme"], "dark");
+    assert_eq!(conf["auto_update"], true);
+    assert_eq!(conf["origin"], "https://chatgpt.com");
+  }
+
+  #[test]
+  fn leaves_a_current_file_alone() {
+    let mut conf = json!({ "schema_version": CONF_SCHEMA_VERSION, "theme": "Dark" });
+    let before = conf.clone();
+    assert_eq!(migrate(&mut conf).unwrap(), CONF_SCHEMA_VERSION);
+    assert_eq!(conf, before);
+  }
+
+  #[test]
+  fn refuses_newer_and_out_of_range_versions() {
+    let mut conf = json!({ "schema_version": CONF_SCHEMA_VERSION + 1 });
+    assert!(matches!(migrate(&mut conf), Err(ConfError::UnsupportedVersion { .. })));
+
+    // u32::MAX + 1 would wrap around to 0 and re-run every migration
+    let mut conf = json!({ "schema_version": u32::MAX as u64 + 1, "theme": "Dark" });
+    let before = conf.clone();
+    assert!(matches!(migrate(&mut conf), Err(ConfError::Migration { .. })));
+    assert_eq!(conf, before);
+
+    let mut conf = json!({ "schema_version": "1" });
+    assert!(matches!(migrate(&mut conf), Err(ConfError::Migration { .. })));
+  }
 }
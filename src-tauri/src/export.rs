@@ -0,0 +1,631 @@
+// src-tauri/src/export.rs  — conversation exports: the message list the page hands over, rendered to GFM and HTML
+
+use std::sync::OnceLock;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use kuchikiki::traits::TendrilSink;
+use kuchikiki::{NodeData, NodeRef};
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use serde::{Deserialize, Serialize};
+use syntect::highlighting::ThemeSet;
+use syntect::html::{css_for_theme_with_class_style, ClassStyle, ClassedHTMLGenerator};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+use crate::conf::now_ms;
+use crate::storage::{self, FileEntry, StorageError};
+use crate::utils::chat_root;
+
+/// A code block of a message, in page order; the page reads the language off
+/// the block header, which the HTML alone does not always carry.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CodeBlock {
+  #[serde(default)]
+  pub lang: String,
+  pub code: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportMessage {
+  /// `user`, `assistant`, `system` or `tool`
+  pub role: String,
+  pub html: String,
+  /// One per `<pre>` of `html`, matched by position.
+  #[serde(default)]
+  pub code: Vec<CodeBlock>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Conversation {
+  pub title: String,
+  #[serde(default)]
+  pub url: String,
+  pub messages: Vec<ExportMessage>,
+}
+
+#[derive(Serialize)]
+struct FrontMatter<'a> {
+  title: &'a str,
+  url: &'a str,
+  exported: String,
+}
+
+// ---------- Markdown ----------
+pub(crate) fn role_title(role: &str) -> String {
+  match role {
+    "assistant" => "ChatGPT".into(),
+    role => {
+      let mut chars = role.chars();
+      chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default()
+    }
+  }
+}
+
+pub fn rfc3339(ms: u64) -> String {
+  DateTime::<Utc>::from_timestamp_millis(ms as i64).unwrap_or_default().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// The whole conversation as one GFM document behind a YAML header.
+pub fn to_markdown(conversation: &Conversation, exported_ms: u64) -> String {
+  let header = FrontMatter { title: &conversation.title, url: &conversation.url, exported: rfc3339(exported_ms) };
+  let mut out = format!("---\n{}---\n\n# {}\n", serde_yaml::to_string(&header).unwrap_or_default(), conversation.title.trim());
+  for message in &conversation.messages {
+    out.push_str(&format!("\n## {}\n\n", role_title(&message.role)));
+    let body = message_markdown(message);
+    if !body.is_empty() {
+      out.push_str(&body);
+      out.push('\n');
+    }
+  }
+  out
+}
+
+pub fn message_markdown(message: &ExportMessage) -> String {
+  let document = kuchikiki::parse_html().one(message.html.as_str()).document_node;
+  let mut ctx = Ctx { code: message.code.iter(), pre_wrap: false };
+  blocks(&document, &mut ctx).join("\n\n")
+}
+
+struct Ctx<'a> {
+  code: std::slice::Iter<'a, CodeBlock>,
+  /// Inside a `whitespace-pre-wrap` element (user messages): newlines are kept.
+  pre_wrap: bool,
+}
+
+fn tag(node: &NodeRef) -> Option<String> {
+  node.as_element().map(|e| e.name.local.to_string())
+}
+
+fn attr(node: &NodeRef, name: &str) -> Option<String> {
+  node.as_element()?.attributes.borrow().get(name).map(str::to_string)
+}
+
+fn has_class(node: &NodeRef, class: &str) -> bool {
+  attr(node, "class").is_some_and(|c| c.split_whitespace().any(|c| c == class))
+}
+
+fn is_block(node: &NodeRef) -> bool {
+  let Some(tag) = tag(node) else { return false };
+  has_class(node, "katex-display")
+    || matches!(
+      tag.as_str(),
+      "p" | "div" | "section" | "article" | "main" | "header" | "footer" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6"
+        | "pre" | "ul" | "ol" | "blockquote" | "table" | "hr" | "body" | "html"
+    )
+}
+
+fn skipped(node: &NodeRef) -> bool {
+  tag(node).is_some_and(|t| matches!(t.as_str(), "head" | "script" | "style" | "button" | "svg" | "template"))
+}
+
+/// Block-level Markdown of the children of `node`; runs of inline children
+/// become paragraphs.
+fn blocks(node: &NodeRef, ctx: &mut Ctx) -> Vec<String> {
+  let mut out = vec![];
+  let mut para = String::new();
+  let flush = |para: &mut String, out: &mut Vec<String>| {
+    let text = para.trim();
+    if !text.is_empty() {
+      out.push(text.to_string());
+    }
+    para.clear();
+  };
+
+  for child in node.children() {
+    if skipped(&child) {
+      continue;
+    }
+    if !is_block(&child) {
+      para.push_str(&inline(&child, ctx));
+      continue;
+    }
+    flush(&mut para, &mut out);
+    out.extend(block(&child, ctx));
+  }
+  flush(&mut para, &mut out);
+  out
+}
+
+fn block(node: &NodeRef, ctx: &mut Ctx) -> Vec<String> {
+  if has_class(node, "katex-display") {
+    return tex(node).map(|tex| format!("$$\n{}\n$$", tex.trim())).into_iter().collect();
+  }
+  let tag = tag(node).unwrap_or_default();
+  match tag.as_str() {
+    "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+      let level = tag[1..].parse().unwrap_or(1);
+      let text = inline_children(node, ctx);
+      if text.trim().is_empty() { vec![] } else { vec![format!("{} {}", "#".repeat(level), text.trim())] }
+    }
+    "pre" => vec![code_block(node, ctx)],
+    "ul" | "ol" => vec![list(node, tag == "ol", ctx)].into_iter().filter(|l| !l.is_empty()).collect(),
+    "blockquote" => {
+      let inner = blocks(node, ctx).join("\n\n");
+      let quoted: Vec<String> = inner.lines().map(|l| if l.is_empty() { ">".into() } else { format!("> {l}") }).collect();
+      if quoted.is_empty() { vec![] } else { vec![quoted.join("\n")] }
+    }
+    "table" => table(node, ctx).into_iter().collect(),
+    "hr" => vec!["---".into()],
+    _ => {
+      let pre_wrap = ctx.pre_wrap;
+      ctx.pre_wrap |= has_class(node, "whitespace-pre-wrap");
+      let out = blocks(node, ctx);
+      ctx.pre_wrap = pre_wrap;
+      out
+    }
+  }
+}
+
+/// TeX source of a KaTeX-rendered formula.
+fn tex(node: &NodeRef) -> Option<String> {
+  node
+    .descendants()
+    .find(|n| tag(n).as_deref() == Some("annotation") && attr(n, "encoding").as_deref() == Some("application/x-tex"))
+    .map(|n| n.text_contents())
+}
+
+/// A fence longer than any backtick run inside `code`.
+fn fence(code: &str, min: usize) -> String {
+  let longest = code.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+  "`".repeat(min.max(longest + 1))
+}
+
+fn code_block(node: &NodeRef, ctx: &mut Ctx) -> String {
+  let (lang, code) = match ctx.code.next() {
+    Some(block) => (block.lang.trim().to_lowercase(), block.code.clone()),
+    None => {
+      let code = node.descendants().find(|n| tag(n).as_deref() == Some("code"));
+      let lang = code
+        .as_ref()
+        .and_then(|c| attr(c, "class"))
+        .and_then(|class| class.split_whitespace().find_map(|c| c.strip_prefix("language-").map(str::to_string)))
+        .unwrap_or_default();
+      (lang, code.unwrap_or_else(|| node.clone()).text_contents())
+    }
+  };
+  let code = code.trim_end_matches('\n');
+  let fence = fence(code, 3);
+  format!("{fence}{lang}\n{code}\n{fence}")
+}
+
+fn list(node: &NodeRef, ordered: bool, ctx: &mut Ctx) -> String {
+  let start: usize = attr(node, "start").and_then(|s| s.parse().ok()).unwrap_or(1);
+  let mut items = vec![];
+  for (i, li) in node.children().filter(|c| tag(c).as_deref() == Some("li")).enumerate() {
+    let mut marker = if ordered { format!("{}. ", start + i) } else { "- ".into() };
+    let indent = " ".repeat(marker.len());
+    if let Some(checkbox) = li.descendants().find(|d| attr(d, "type").as_deref() == Some("checkbox")) {
+      marker.push_str(if attr(&checkbox, "checked").is_some() { "[x] " } else { "[ ] " });
+    }
+    let body = blocks(&li, ctx).join("\n\n");
+    let mut lines = body.lines();
+    let mut item = format!("{marker}{}", lines.next().unwrap_or_default());
+    for line in lines {
+      item.push('\n');
+      if !line.is_empty() {
+        item.push_str(&indent);
+        item.push_str(line);
+      }
+    }
+    items.push(item);
+  }
+  items.join("\n")
+}
+
+fn table(node: &NodeRef, ctx: &mut Ctx) -> Option<String> {
+  let rows: Vec<NodeRef> = node.descendants().filter(|d| tag(d).as_deref() == Some("tr")).collect();
+  let mut cells: Vec<Vec<String>> = vec![];
+  let mut align = vec![];
+  for (i, row) in rows.iter().enumerate() {
+    let row_cells: Vec<NodeRef> = row.children().filter(|c| matches!(tag(c).as_deref(), Some("th" | "td"))).collect();
+    if i == 0 {
+      align = row_cells.iter().map(alignment).collect();
+    }
+    let text = row_cells.iter().map(|c| inline_children(c, ctx).trim().replace('|', "\\|").replace('\n', "<br>")).collect();
+    cells.push(text);
+  }
+  let width = cells.iter().map(Vec::len).max().filter(|w| *w > 0)?;
+  align.resize(width, "---");
+
+  let line = |row: &[String]| {
+    let mut row = row.to_vec();
+    row.resize(width, String::new());
+    format!("| {} |", row.join(" | "))
+  };
+  let mut out = vec![line(&cells[0]), format!("|{}|", align.join("|"))];
+  out.extend(cells[1..].iter().map(|r| line(r)));
+  Some(out.join("\n"))
+}
+
+fn alignment(cell: &NodeRef) -> &'static str {
+  let style = attr(cell, "style").unwrap_or_default().replace(' ', "");
+  let align = attr(cell, "align").unwrap_or_default();
+  if align == "center" || style.contains("text-align:center") {
+    ":---:"
+  } else if align == "right" || style.contains("text-align:right") {
+    "---:"
+  } else {
+    "---"
+  }
+}
+
+// ---------- Inline ----------
+fn inline_children(node: &NodeRef, ctx: &mut Ctx) -> String {
+  node.children().filter(|c| !skipped(c)).map(|c| inline(&c, ctx)).collect()
+}
+
+/// Markdown specials, and `$` so that only real math reads as math.
+fn escape(text: &str) -> String {
+  let chars: Vec<char> = text.chars().collect();
+  let mut out = String::with_capacity(text.len());
+  for (i, &c) in chars.iter().enumerate() {
+    let intraword = c == '_'
+      && i > 0
+      && chars[i - 1].is_alphanumeric()
+      && chars.get(i + 1).is_some_and(|n| n.is_alphanumeric());
+    if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '$' | '<') && !intraword {
+      out.push('\\');
+    }
+    out.push(c);
+  }
+  out
+}
+
+fn text(raw: &str, ctx: &Ctx) -> String {
+  if ctx.pre_wrap {
+    let lines: Vec<String> = raw.split('\n').map(|l| escape(l.trim_end())).collect();
+    let mut out = lines[0].clone();
+    for pair in lines.windows(2) {
+      out.push_str(if pair[0].is_empty() || pair[1].is_empty() { "\n" } else { "  \n" });
+      out.push_str(&pair[1]);
+    }
+    return out;
+  }
+  let mut collapsed = String::with_capacity(raw.len());
+  for c in raw.chars() {
+    if c.is_whitespace() {
+      if !collapsed.ends_with(' ') {
+        collapsed.push(' ');
+      }
+    } else {
+      collapsed.push(c);
+    }
+  }
+  escape(&collapsed)
+}
+
+/// `**x**` and friends, with surrounding spaces moved outside the markers.
+fn wrap(inner: &str, marker: &str) -> String {
+  let trimmed = inner.trim();
+  if trimmed.is_empty() {
+    return inner.to_string();
+  }
+  let lead = &inner[..inner.len() - inner.trim_start().len()];
+  let trail = &inner[inner.trim_end().len()..];
+  format!("{lead}{marker}{trimmed}{marker}{trail}")
+}
+
+fn inline(node: &NodeRef, ctx: &mut Ctx) -> String {
+  if let NodeData::Text(raw) = node.data() {
+    return text(&raw.borrow(), ctx);
+  }
+  if has_class(node, "katex") {
+    return tex(node).map(|tex| format!("${}$", tex.trim())).unwrap_or_default();
+  }
+  let Some(tag) = tag(node) else { return String::new() };
+  match tag.as_str() {
+    "br" => "  \n".into(),
+    "strong" | "b" => wrap(&inline_children(node, ctx), "**"),
+    "em" | "i" => wrap(&inline_children(node, ctx), "*"),
+    "del" | "s" => wrap(&inline_children(node, ctx), "~~"),
+    "code" => {
+      let code = node.text_contents();
+      let fence = fence(&code, 1);
+      let pad = if code.starts_with('`') || code.ends_with('`') { " " } else { "" };
+      format!("{fence}{pad}{code}{pad}{fence}")
+    }
+    "a" => {
+      let label = inline_children(node, ctx);
+      match attr(node, "href").filter(|h| !h.is_empty() && !h.starts_with("javascript:")) {
+        Some(href) if !label.trim().is_empty() => format!("[{}]({})", label.trim(), href.replace(' ', "%20").replace(')', "%29")),
+        _ => label,
+      }
+    }
+    "img" => match attr(node, "src").filter(|s| s.starts_with("http")) {
+      Some(src) => format!("![{}]({src})", escape(&attr(node, "alt").unwrap_or_default())),
+      None => String::new(),
+    },
+    "input" => String::new(),
+    _ => {
+      let pre_wrap = ctx.pre_wrap;
+      ctx.pre_wrap |= has_class(node, "whitespace-pre-wrap");
+      let out = inline_children(node, ctx);
+      ctx.pre_wrap = pre_wrap;
+      out
+    }
+  }
+}
+
+// ---------- HTML ----------
+/// Messages longer than this (in Markdown characters) start collapsed.
+const LONG_MESSAGE_CHARS: usize = 3000;
+const LIGHT_THEME: &str = "InspiredGitHub";
+const DARK_THEME: &str = "base16-ocean.dark";
+
+const HTML_CSS: &str = r#"
+:root{--bg:#fff;--fg:#1f2328;--muted:#656d76;--border:#d0d7de;--card:#f6f8fa;--user:#eef4ff;color-scheme:light}
+html[data-theme=dark]{--bg:#0d1117;--fg:#e6edf3;--muted:#8d96a0;--border:#30363d;--card:#161b22;--user:#1c2333;color-scheme:dark}
+@media (prefers-color-scheme:dark){html:not([data-theme=light]){--bg:#0d1117;--fg:#e6edf3;--muted:#8d96a0;--border:#30363d;--card:#161b22;--user:#1c2333;color-scheme:dark}}
+*{box-sizing:border-box}
+body{margin:0;background:var(--bg);color:var(--fg);font:15px/1.6 system-ui,-apple-system,"Segoe UI",Roboto,sans-serif}
+main{max-width:860px;margin:0 auto;padding:32px 20px 64px}
+header{border-bottom:1px solid var(--border);margin-bottom:24px;padding-bottom:12px}
+header h1{margin:0 0 4px;font-size:26px}
+header .meta{color:var(--muted);font-size:13px;word-break:break-all}
+header .meta a{color:inherit}
+#theme{float:right;border:1px solid var(--border);background:var(--card);color:var(--fg);border-radius:6px;padding:4px 10px;cursor:pointer}
+details.msg{border:1px solid var(--border);border-radius:10px;margin:0 0 16px;background:var(--card)}
+details.msg.user{background:var(--user)}
+details.msg>summary{cursor:pointer;padding:10px 16px;font-weight:600;list-style:none}
+details.msg>summary::-webkit-details-marker{display:none}
+details.msg>summary::before{content:"\25B8";display:inline-block;width:1.2em;transition:transform .15s}
+details.msg[open]>summary::before{transform:rotate(90deg)}
+details.msg .preview{font-weight:400;color:var(--muted);margin-left:8px}
+details.msg[open] .preview{display:none}
+.body{padding:0 16px 8px;overflow-wrap:anywhere}
+pre{padding:12px 14px;border-radius:8px;overflow:auto;font-size:13px;border:1px solid var(--border)}
+code{font-family:ui-monospace,SFMono-Regular,Menlo,Consolas,monospace}
+:not(pre)>code{background:var(--bg);border:1px solid var(--border);border-radius:4px;padding:0 4px;font-size:.9em}
+table{border-collapse:collapse;display:block;overflow:auto}
+th,td{border:1px solid var(--border);padding:6px 12px}
+blockquote{margin:0;padding:0 1em;color:var(--muted);border-left:4px solid var(--border)}
+.math{font-family:"Latin Modern Math","STIX Two Math","Cambria Math",serif;font-style:italic}
+.math-display{display:block;text-align:center;margin:1em 0;overflow:auto}
+img{max-width:100%}
+"#;
+
+const HTML_JS: &str = r#"
+(function(){
+  var root=document.documentElement,key='chat-export-theme';
+  try{var saved=localStorage.getItem(key);if(saved)root.dataset.theme=saved;}catch(e){}
+  document.getElementById('theme').onclick=function(){
+    var dark=root.dataset.theme?root.dataset.theme==='dark':matchMedia('(prefers-color-scheme: dark)').matches;
+    root.dataset.theme=dark?'light':'dark';
+    try{localStorage.setItem(key,root.dataset.theme);}catch(e){}
+  };
+})();
+"#;
+
+struct Highlighter {
+  syntaxes: SyntaxSet,
+  css: String,
+}
+
+fn highlighter() -> &'static Highlighter {
+  static HIGHLIGHTER: OnceLock<Highlighter> = OnceLock::new();
+  HIGHLIGHTER.get_or_init(|| {
+    let themes = ThemeSet::load_defaults();
+    let css = |name: &str, scopes: &[&str]| {
+      let css = themes.themes.get(name).and_then(|t| css_for_theme_with_class_style(t, HL_CLASS).ok()).unwrap_or_default();
+      scope_css(&css, scopes)
+    };
+    let light = css(LIGHT_THEME, &[""]);
+    let dark = css(DARK_THEME, &["html[data-theme=dark] "]);
+    let auto = css(DARK_THEME, &["html:not([data-theme=light]) "]);
+    Highlighter {
+      syntaxes: SyntaxSet::load_defaults_newlines(),
+      css: format!("{light}\n{dark}\n@media (prefers-color-scheme:dark){{\n{auto}}}\n"),
+    }
+  })
+}
+
+const HL_CLASS: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "hl-" };
+
+/// Prepends `scopes` to every selector of syntect's generated CSS, which puts
+/// one rule head per line.
+fn scope_css(css: &str, scopes: &[&str]) -> String {
+  let mut out = String::with_capacity(css.len() * 2);
+  for line in css.lines() {
+    match line.strip_suffix(" {") {
+      Some(selectors) if !line.starts_with(' ') => {
+        let scoped: Vec<String> = selectors
+          .split(", ")
+          .flat_map(|sel| scopes.iter().map(move |scope| format!("{scope}{sel}")))
+          .collect();
+        out.push_str(&scoped.join(", "));
+        out.push_str(" {");
+      }
+      _ => out.push_str(line),
+    }
+    out.push('\n');
+  }
+  out
+}
+
+fn escape_html(text: &str) -> String {
+  let mut out = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '&' => out.push_str("&amp;"),
+      '<' => out.push_str("&lt;"),
+      '>' => out.push_str("&gt;"),
+      '"' => out.push_str("&quot;"),
+      '\'' => out.push_str("&#39;"),
+      c => out.push(c),
+    }
+  }
+  out
+}
+
+fn highlight(code: &str, lang: &str) -> String {
+  let hl = highlighter();
+  let syntax = hl.syntaxes.find_syntax_by_token(lang).unwrap_or_else(|| hl.syntaxes.find_syntax_plain_text());
+  let mut html = ClassedHTMLGenerator::new_with_class_style(syntax, &hl.syntaxes, HL_CLASS);
+  for line in LinesWithEndings::from(code) {
+    if html.parse_html_for_line_which_includes_newline(line).is_err() {
+      return format!("<pre><code>{}</code></pre>", escape_html(code));
+    }
+  }
+  format!("<pre class=\"hl-code\" data-lang=\"{}\"><code>{}</code></pre>", escape_html(lang), html.finalize())
+}
+
+/// GFM to HTML, with fenced code run through the highlighter.
+fn markdown_html(markdown: &str) -> String {
+  let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS | Options::ENABLE_MATH;
+  let mut events = vec![];
+  let mut code: Option<(String, String)> = None;
+  for event in Parser::new_ext(markdown, options) {
+    match event {
+      Event::Start(Tag::CodeBlock(kind)) => {
+        let lang = match kind {
+          CodeBlockKind::Fenced(info) => info.split_whitespace().next().unwrap_or_default().to_string(),
+          CodeBlockKind::Indented => String::new(),
+        };
+        code = Some((lang, String::new()));
+      }
+      Event::Text(text) => match &mut code {
+        Some((_, buf)) => buf.push_str(&text),
+        None => events.push(Event::Text(text)),
+      },
+      Event::End(TagEnd::CodeBlock) => {
+        let (lang, buf) = code.take().unwrap_or_default();
+        events.push(Event::Html(highlight(&buf, &lang).into()));
+      }
+      event => events.push(event),
+    }
+  }
+  let mut html = String::new();
+  pulldown_cmark::html::push_html(&mut html, events.into_iter());
+  html
+}
+
+fn message_html(message: &ExportMessage) -> String {
+  let markdown = message_markdown(message);
+  let long = markdown.chars().count() > LONG_MESSAGE_CHARS;
+  let preview = if long {
+    let first = markdown.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or_default();
+    let first: String = first.chars().take(120).collect();
+    format!("<span class=\"preview\">{}…</span>", escape_html(&first))
+  } else {
+    String::new()
+  };
+  format!(
+    "<details class=\"msg {role}\"{open}><summary>{title}{preview}</summary><div class=\"body\">{body}</div></details>\n",
+    role = escape_html(&message.role),
+    open = if long { "" } else { " open" },
+    title = escape_html(&role_title(&message.role)),
+    body = markdown_html(&markdown),
+  )
+}
+
+/// One offline page: inlined styles, highlighted code, a light/dark toggle
+/// that defaults to the system setting, and long messages folded.
+pub fn to_html(conversation: &Conversation, exported_ms: u64) -> String {
+  let title = escape_html(conversation.title.trim());
+  let url = escape_html(&conversation.url);
+  let messages: String = conversation.messages.iter().map(message_html).collect();
+  format!(
+    "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width,initial-scale=1\">\n<title>{title}</title>\n<style>{HTML_CSS}{hl}</style>\n</head>\n<body>\n<main>\n<header><button id=\"theme\" type=\"button\">Light / Dark</button><h1>{title}</h1><div class=\"meta\"><a href=\"{url}\">{url}</a> · exported {exported}</div></header>\n{messages}</main>\n<script>{HTML_JS}</script>\n</body>\n</html>\n",
+    hl = highlighter().css,
+    exported = rfc3339(exported_ms),
+  )
+}
+
+// ---------- Tauri commands ----------
+/// Renders `conversation` and stores it as `notes/<id>.md`, listed in
+/// `chat.notes.json` under its title.
+#[tauri::command]
+pub fn export_markdown(id: String, conversation: Conversation) -> Result<Option<FileEntry>, StorageError> {
+  let markdown = to_markdown(&conversation, now_ms());
+  storage::store(&chat_root(), &format!("notes/{id}.md"), markdown.as_bytes(), Some(&conversation.title))
+}
+
+/// Renders `conversation` as a self-contained page at `download/html/<id>.html`,
+/// listed in `chat.download.json` under its title.
+#[tauri::command]
+pub fn export_html(id: String, conversation: Conversation) -> Result<Option<FileEntry>, StorageError> {
+  let html = to_html(&conversation, now_ms());
+  storage::store(&chat_root(), &format!("download/html/{id}.html"), html.as_bytes(), Some(&conversation.title))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn md(html: &str, code: Vec<CodeBlock>) -> String {
+    message_markdown(&ExportMessage { role: "assistant".into(), html: html.into(), code })
+  }
+
+  #[test]
+  fn table_with_alignment_and_pipes() {
+    let html = r#"<table>
+      <thead><tr><th>Name</th><th style="text-align: center">A|B</th><th align="right">N</th></tr></thead>
+      <tbody><tr><td>x_y</td><td>a|b</td><td>1</td></tr><tr><td>z</td></tr></tbody>
+    </table>"#;
+    assert_eq!(md(html, vec![]), "| Name | A\\|B | N |\n|---|:---:|---:|\n| x_y | a\\|b | 1 |\n| z |  |  |");
+  }
+
+  #[test]
+  fn code_fence_longer_than_backticks_inside() {
+    let html = r#"<pre><code class="language-rust">let s = "```";
+</code></pre>"#;
+    assert_eq!(md(html, vec![]), "````rust\nlet s = \"```\";\n````");
+    // the language from the page's block header wins over the HTML
+    let block = CodeBlock { lang: "Python".into(), code: "print(1)\n".into() };
+    assert_eq!(md("<pre><code>ignored</code></pre>", vec![block]), "```python\nprint(1)\n```");
+    assert_eq!(md("<p>run <code>a`b</code></p>", vec![]), "run ``a`b``");
+  }
+
+  #[test]
+  fn katex_becomes_dollar_math() {
+    let formula = |tex: &str| {
+      format!(
+        r#"<span class="katex"><span class="katex-mathml"><math><semantics><mrow><mi>x</mi></mrow><annotation encoding="application/x-tex">{tex}</annotation></semantics></math></span><span class="katex-html" aria-hidden="true">x</span></span>"#
+      )
+    };
+    let html = format!(
+      r#"<p>Euler: {} costs $5</p><span class="katex-display">{}</span>"#,
+      formula(r"e^{i\pi}+1=0"),
+      formula("x^2")
+    );
+    assert_eq!(md(&html, vec![]), "Euler: $e^{i\\pi}+1=0$ costs \\$5\n\n$$\nx^2\n$$");
+  }
+
+  #[test]
+  fn ordered_list_start_nesting_and_tasks() {
+    let html = r#"<ol start="3"><li>three</li><li>four<ul><li>nested</li></ul></li></ol>
+      <ul><li><input type="checkbox" checked> done</li><li><input type="checkbox"> todo</li></ul>"#;
+    assert_eq!(md(html, vec![]), "3. three\n4. four\n\n   - nested\n\n- [x] done\n- [ ] todo");
+  }
+
+  #[test]
+  fn escapes_specials_but_not_intraword_underscores() {
+    assert_eq!(md("<p>a *b* snake_case _x_ [l] &lt;tag&gt;</p>", vec![]), r"a \*b\* snake_case \_x\_ \[l\] \<tag>");
+  }
+
+  #[test]
+  fn pre_wrap_keeps_line_breaks() {
+    let html = "<div class=\"whitespace-pre-wrap\">line one\nline two\n\npara</div>";
+    assert_eq!(md(html, vec![]), "line one  \nline two\n\npara");
+  }
+}
@@ -6,7 +6,7 @@
 
 use serde::{ser::Serializer, Serialize};
 use std::{
-    path::PathBuf,
+    path::{Path, PathBuf},
     time::{SystemTime, UNIX_EPOCH},
 };
 use tauri::command;
@@ -61,7 +61,7 @@ struct UnixMetadata {
 pub struct Metadata {
     accessed_at_ms: u64,
     pub created_at_ms: u64,
-    modified_at_ms: u64,
+    pub modified_at_ms: u64,
     is_dir: bool,
     is_file: bool,
     is_symlink: bool,
@@ -84,6 +84,10 @@ pub fn system_time_to_ms(time: std::io::Result<SystemTime>) -> u64 {
 
 #[command]
 pub async fn metadata(path: PathBuf) -> Result<Metadata> {
+    read_metadata(&path)
+}
+
+pub fn read_metadata(path: &Path) -> Result<Metadata> {
     let metadata = std::fs::metadata(path)?;
     let file_type = metadata.file_type();
     let permissions = metadata.permissions();
@@ -0,0 +1,303 @@
+// src-tauri/src/history.rs  — per-source sync history (snapshots + changes), diffs and rollback
+
+use std::{collections::HashMap, fs, path::Path, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+
+use crate::cmd_index;
+use crate::conf::now_ms;
+use crate::prompts::{self, PromptRecord, CHAT_PROMPTS_CSV, SYNC_PROMPTS_ID};
+use crate::sources;
+use crate::utils::{chat_root, create_file};
+
+pub const HISTORY_DIR: &str = "sync_history";
+/// Snapshots kept per source; older ones are pruned on each sync.
+pub const HISTORY_LIMIT: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+  Added,
+  Removed,
+  Modified,
+}
+
+/// One prompt that differs between two snapshots, matched by `act`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptChange {
+  pub act: String,
+  pub kind: ChangeKind,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub old: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub new: Option<String>,
+}
+
+/// `sync_history/<source_id>/<timestamp>.json`: the records right after a
+/// sync, and what changed compared to the one before.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+  pub source_id: String,
+  pub source_name: String,
+  pub timestamp: u64,
+  /// Set when this snapshot was written by a rollback to that snapshot.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub rollback_of: Option<String>,
+  pub changes: Vec<PromptChange>,
+  pub records: Vec<PromptRecord>,
+}
+
+/// Row of `list_sync_history`; `id` is `<source_id>@<timestamp>`.
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryEntry {
+  pub id: String,
+  pub source_id: String,
+  pub source_name: String,
+  pub timestamp: u64,
+  pub rollback_of: Option<String>,
+  pub added: usize,
+  pub removed: usize,
+  pub modified: usize,
+}
+
+/// A word-level piece of a modified prompt.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffSpan {
+  /// `equal`, `insert` or `delete`
+  pub tag: &'static str,
+  pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffChange {
+  #[serde(flatten)]
+  pub change: PromptChange,
+  /// Only for `modified`.
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub spans: Vec<DiffSpan>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncDiff {
+  pub from: HistoryEntry,
+  pub to: HistoryEntry,
+  pub changes: Vec<DiffChange>,
+}
+
+// ---------- Paths + ids ----------
+fn source_dir(root: &Path, source_id: &str) -> PathBuf {
+  root.join(HISTORY_DIR).join(source_id)
+}
+
+fn parse_id(id: &str) -> Result<(&str, u64), String> {
+  let invalid = || format!("\"{id}\" is not a sync history id");
+  let (source_id, ts) = id.rsplit_once('@').ok_or_else(invalid)?;
+  if source_id.is_empty() || source_id.contains(['/', '\\']) || source_id.starts_with('.') {
+    return Err(invalid());
+  }
+  Ok((source_id, ts.parse().map_err(|_| invalid())?))
+}
+
+fn read_snapshot(root: &Path, id: &str) -> Result<Snapshot, String> {
+  let (source_id, ts) = parse_id(id)?;
+  let path = source_dir(root, source_id).join(format!("{ts}.json"));
+  let bytes = fs::read(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+  serde_json::from_slice(&bytes).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+fn entry(snapshot: &Snapshot) -> HistoryEntry {
+  let count = |kind| snapshot.changes.iter().filter(|c| c.kind == kind).count();
+  HistoryEntry {
+    id: format!("{}@{}", snapshot.source_id, snapshot.timestamp),
+    source_id: snapshot.source_id.clone(),
+    source_name: snapshot.source_name.clone(),
+    timestamp: snapshot.timestamp,
+    rollback_of: snapshot.rollback_of.clone(),
+    added: count(ChangeKind::Added),
+    removed: count(ChangeKind::Removed),
+    modified: count(ChangeKind::Modified),
+  }
+}
+
+// ---------- Recording ----------
+/// Prompts of `after` in its order, then the ones only `before` had.
+pub fn changes(before: &[PromptRecord], after: &[PromptRecord]) -> Vec<PromptChange> {
+  let old: HashMap<&str, &str> = before.iter().map(|r| (r.act.as_str(), r.prompt.as_str())).collect();
+  let new: HashMap<&str, &str> = after.iter().map(|r| (r.act.as_str(), r.prompt.as_str())).collect();
+
+  let mut changes = vec![];
+  for r in after {
+    match old.get(r.act.as_str()) {
+      None => changes.push(PromptChange {
+        act: r.act.clone(),
+        kind: ChangeKind::Added,
+        old: None,
+        new: Some(r.prompt.clone()),
+      }),
+      Some(prompt) if *prompt != r.prompt => changes.push(PromptChange {
+        act: r.act.clone(),
+        kind: ChangeKind::Modified,
+        old: Some(prompt.to_string()),
+        new: Some(r.prompt.clone()),
+      }),
+      Some(_) => {}
+    }
+  }
+  for r in before.iter().filter(|r| !new.contains_key(r.act.as_str())) {
+    changes.push(PromptChange {
+      act: r.act.clone(),
+      kind: ChangeKind::Removed,
+      old: Some(r.prompt.clone()),
+      new: None,
+    });
+  }
+  changes
+}
+
+/// Stores a snapshot when a sync changed the prompts of `source_id`, then
+/// prunes the oldest beyond `HISTORY_LIMIT`.
+pub fn record(
+  root: &Path,
+  source_id: &str,
+  source_name: &str,
+  before: &[PromptRecord],
+  after: &[PromptRecord],
+  rollback_of: Option<String>,
+) -> anyhow::Result<Option<HistoryEntry>> {
+  let changes = changes(before, after);
+  if changes.is_empty() && rollback_of.is_none() {
+    return Ok(None);
+  }
+  let dir = source_dir(root, source_id);
+  let mut timestamp = now_ms();
+  while dir.join(format!("{timestamp}.json")).exists() {
+    timestamp += 1;
+  }
+  let snapshot = Snapshot {
+    source_id: source_id.into(),
+    source_name: source_name.into(),
+    timestamp,
+    rollback_of,
+    changes,
+    records: after.to_vec(),
+  };
+  create_file(dir.join(format!("{timestamp}.json")), serde_json::to_vec_pretty(&snapshot)?)?;
+
+  let stamps = timestamps(&dir);
+  for ts in stamps.iter().take(stamps.len().saturating_sub(HISTORY_LIMIT)) {
+    let _ = fs::remove_file(dir.join(format!("{ts}.json")));
+  }
+  Ok(Some(entry(&snapshot)))
+}
+
+/// Snapshot timestamps of one source, oldest first.
+fn timestamps(dir: &Path) -> Vec<u64> {
+  let mut stamps: Vec<u64> = fs::read_dir(dir)
+    .into_iter()
+    .flatten()
+    .flatten()
+    .filter_map(|e| e.path().file_stem()?.to_str()?.parse().ok())
+    .collect();
+  stamps.sort();
+  stamps
+}
+
+// ---------- Diff ----------
+fn spans(old: &str, new: &str) -> Vec<DiffSpan> {
+  let mut spans: Vec<DiffSpan> = vec![];
+  for change in TextDiff::from_words(old, new).iter_all_changes() {
+    let tag = match change.tag() {
+      ChangeTag::Equal => "equal",
+      ChangeTag::Insert => "insert",
+      ChangeTag::Delete => "delete",
+    };
+    match spans.last_mut() {
+      Some(last) if last.tag == tag => last.text.push_str(change.value()),
+      _ => spans.push(DiffSpan { tag, text: change.value().to_string() }),
+    }
+  }
+  spans
+}
+
+pub fn diff(root: &Path, a: &str, b: &str) -> Result<SyncDiff, String> {
+  let (from, to) = (read_snapshot(root, a)?, read_snapshot(root, b)?);
+  if from.source_id != to.source_id {
+    return Err(format!("{a} and {b} belong to different sources"));
+  }
+  let changes = changes(&from.records, &to.records)
+    .into_iter()
+    .map(|change| {
+      let spans = match (&change.kind, &change.old, &change.new) {
+        (ChangeKind::Modified, Some(old), Some(new)) => spans(old, new),
+        _ => vec![],
+      };
+      DiffChange { change, spans }
+    })
+    .collect();
+  Ok(SyncDiff { from: entry(&from), to: entry(&to), changes })
+}
+
+// ---------- Rollback ----------
+/// Puts the records of snapshot `id` back into `cache_model/`, keeping the
+/// current enable flags, and records the rollback as a new snapshot. The
+/// source's last good copy is dropped, so a failing sync cannot fall back to
+/// the payload that was rolled back.
+pub fn rollback(root: &Path, id: &str) -> anyhow::Result<HistoryEntry> {
+  let snapshot = read_snapshot(root, id).map_err(anyhow::Error::msg)?;
+  let current = prompts::read_cache(root, &snapshot.source_id);
+  let enabled: HashMap<&str, bool> = current.iter().map(|r| (r.act.as_str(), r.enable)).collect();
+  let mut records = snapshot.records.clone();
+  for r in &mut records {
+    r.enable = enabled.get(r.act.as_str()).copied().unwrap_or(r.enable);
+  }
+
+  prompts::write_cache(root, &snapshot.source_id, &records)?;
+  sources::forget_cached(&snapshot.source_id);
+  if snapshot.source_id == SYNC_PROMPTS_ID {
+    create_file(root.join(CHAT_PROMPTS_CSV), prompts::write_csv(&records)?)?;
+  }
+  let entry = record(root, &snapshot.source_id, &snapshot.source_name, &current, &records, Some(id.to_string()))?;
+  entry.ok_or_else(|| anyhow::anyhow!("rollback of {id} was not recorded"))
+}
+
+// ---------- Tauri commands ----------
+/// Newest first; all sources unless `source_id` is given.
+#[tauri::command]
+pub fn list_sync_history(source_id: Option<String>) -> Result<Vec<HistoryEntry>, String> {
+  let root = chat_root();
+  let sources: Vec<String> = match source_id {
+    Some(id) => vec![id],
+    None => fs::read_dir(root.join(HISTORY_DIR))
+      .into_iter()
+      .flatten()
+      .flatten()
+      .filter(|e| e.path().is_dir())
+      .filter_map(|e| e.file_name().into_string().ok())
+      .collect(),
+  };
+
+  let mut entries = vec![];
+  for source_id in sources {
+    for ts in timestamps(&source_dir(&root, &source_id)) {
+      if let Ok(snapshot) = read_snapshot(&root, &format!("{source_id}@{ts}")) {
+        entries.push(entry(&snapshot));
+      }
+    }
+  }
+  entries.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
+  Ok(entries)
+}
+
+/// What changed going from snapshot `a` to snapshot `b` of the same source.
+#[tauri::command]
+pub fn diff_sync(a: String, b: String) -> Result<SyncDiff, String> {
+  diff(&chat_root(), &a, &b)
+}
+
+#[tauri::command]
+pub fn rollback_sync(id: String) -> Result<HistoryEntry, String> {
+  let entry = rollback(&chat_root(), &id).map_err(|e| e.to_string())?;
+  cmd_index::rebuild().map_err(|e| e.to_string())?;
+  Ok(entry)
+}
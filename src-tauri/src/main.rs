@@ -1,19 +1,55 @@
 #![cfg_attr(all(not(debug_assertions), target_os = "windows"), windows_subsystem = "windows")]
 
+mod archive;
+mod bundle;
+mod cmd_index;
+mod history;
 mod menu;
 mod conf;
+mod export;
+mod fs_extra;
+mod overrides;
+mod parser;
+mod pdf;
+mod policy;
+mod profile;
+mod prompts;
+mod scheduler;
+mod search;
+mod sources;
+mod storage;
+mod template;
 mod utils;
+mod watch;
 
-use crate::conf::{get_chat_conf, reset_chat_conf, set_chat_conf, ChatConfJson};
+use crate::conf::{
+  get_chat_conf, get_chat_conf_path, list_conf_backups, patch_chat_conf, reset_chat_conf, restore_conf_backup, set_chat_conf, ChatConfJson,
+};
+use crate::archive::import_openai_export;
+use crate::bundle::{export_bundle, import_bundle, preview_bundle};
+use crate::cmd_index::{cmd_list, get_chat_model_cmd};
+use crate::export::{export_html, export_markdown};
+use crate::history::{diff_sync, list_sync_history, rollback_sync};
 use crate::menu::{build_menu, handle_menu_event};
-use crate::utils::{open_external, run_check_update, set_theme_all, get_app_info};
+use crate::overrides::get_effective_conf;
+use crate::parser::parse_prompt;
+use crate::pdf::export_pdf;
+use crate::profile::{create_profile, list_profiles, open_profile};
+use crate::prompts::sync_prompts;
+use crate::scheduler::set_online;
+use crate::search::search_prompts;
+use crate::sources::sync_user_prompts;
+use crate::storage::{download, download_list, save_file};
+use crate::template::{prompt_vars, render_prompt};
+use crate::utils::{get_app_info, get_chat_root, open_external, run_check_update, set_theme_all, window_reload};
 
 use tauri::{
-  AppHandle, Builder, Emitter, Manager, Result, Theme,
+  AppHandle, Builder, Manager, Result, RunEvent,
   WebviewUrl, WebviewWindow, WebviewWindowBuilder, Wry,
 };
 use tauri::tray::{TrayIcon, TrayIconBuilder};
 use tauri::webview::PageLoadEvent;
+use tauri_plugin_fs::FsExt;
 
 // ===== Loader overlay injectors =============================================
 const LOADER_INJECT_JS: &str = r#"
@@ -63,6 +99,9 @@ fn build_tray(app: &tauri::AppHandle) -> tauri::Result<TrayIcon> {
     .on_tray_icon_event(|tray, event| {
       if let tauri::tray::TrayIconEvent::Click { .. } = event {
         let app = tray.app_handle();
+        if ChatConfJson::load().tray {
+          return toggle_tray_window(app);
+        }
         for w in app.webview_windows().values() {
           let _ = w.show();
           let _ = w.set_focus();
@@ -79,17 +118,111 @@ fn any_app_window(app: &AppHandle) -> Option<WebviewWindow<Wry>> {
   app.webview_windows().values().next().cloned()
 }
 
+pub(crate) fn build_core_window<M: Manager<Wry>>(manager: &M, conf: &ChatConfJson) -> Result<WebviewWindow<Wry>> {
+  #[cfg(debug_assertions)]
+  let url = WebviewUrl::External("http://localhost:1420".parse().unwrap());
+  #[cfg(not(debug_assertions))]
+  let url = WebviewUrl::External(conf.origin.url().clone());
+
+  let mut builder = WebviewWindowBuilder::new(manager, "core", url)
+    .resizable(true)
+    .visible(true)
+    .title(window_title())
+    .always_on_top(conf.stay_on_top)
+    .initialization_script(LOADER_INJECT_JS)
+    .initialization_script(LOADER_SHOW_JS)
+    .initialization_script(VIRTUALIZER_JS)
+    .initialization_script(VIRTUALIZER_LOADER_JS);
+  if !conf.ua_window.is_empty() {
+    builder = builder.user_agent(&conf.ua_window);
+  }
+  if let Some(dir) = profile::webview_data_dir() {
+    builder = builder.data_directory(dir);
+  }
+  builder.build()
+}
+
+/// The small window the tray icon toggles when `tray` is on; it has its own
+/// user agent (`ua_tray`) and is built on first use.
+pub(crate) fn build_tray_window<M: Manager<Wry>>(manager: &M, conf: &ChatConfJson) -> Result<WebviewWindow<Wry>> {
+  let url = WebviewUrl::External(conf.origin.url().clone());
+  let mut builder = WebviewWindowBuilder::new(manager, "tray", url)
+    .inner_size(360.0, 540.0)
+    .resizable(false)
+    .decorations(false)
+    .skip_taskbar(true)
+    .visible(false)
+    .always_on_top(true)
+    .initialization_script(LOADER_INJECT_JS)
+    .initialization_script(VIRTUALIZER_JS)
+    .initialization_script(VIRTUALIZER_LOADER_JS);
+  if !conf.ua_tray.is_empty() {
+    builder = builder.user_agent(&conf.ua_tray);
+  }
+  if let Some(dir) = profile::webview_data_dir() {
+    builder = builder.data_directory(dir);
+  }
+  builder.build()
+}
+
+fn toggle_tray_window(app: &AppHandle) {
+  let win = match app.get_webview_window("tray") {
+    Some(win) => win,
+    None => match build_tray_window(app, &ChatConfJson::load()) {
+      Ok(win) => win,
+      Err(e) => return eprintln!("Failed to open the tray window: {e}"),
+    },
+  };
+  if win.is_visible().unwrap_or(false) {
+    let _ = win.hide();
+  } else {
+    let _ = win.show();
+    let _ = win.set_focus();
+  }
+}
+
+fn window_title() -> String {
+  if profile::is_default() {
+    "ChatGPT".into()
+  } else {
+    format!("ChatGPT — {}", profile::active())
+  }
+}
+
 // ---- app entry --------------------------------------------------------------
 fn main() -> Result<()> {
+  profile::init(std::env::args().skip(1));
+  overrides::init(std::env::args().skip(1));
+  if let Err(e) = utils::migrate_legacy_home() {
+    eprintln!("Failed to move ~/.chatgpt to the XDG directories: {e}");
+  }
+  if let Err(e) = utils::migrate_default_profile() {
+    eprintln!("Failed to move the default profile to its own directory: {e}");
+  }
+  let context = tauri::generate_context!();
+  let dbus_id = profile::dbus_id(&context.config().identifier);
+
   Builder::default()
     // plugins (Linux-safe)
     .plugin(tauri_plugin_dialog::init())
     .plugin(tauri_plugin_fs::init())
     .plugin(tauri_plugin_shell::init())
     .plugin(tauri_plugin_process::init())
-    .plugin(tauri_plugin_single_instance::init(|_, _, _| {}))
+    .plugin(
+      tauri_plugin_single_instance::Builder::new()
+        .dbus_id(dbus_id)
+        .callback(|app, _, _| {
+          // the same profile was launched again: bring its window forward
+          if let Some(core) = app.get_webview_window("core") {
+            let _ = core.show();
+            let _ = core.set_focus();
+          }
+        })
+        .build(),
+    )
     .plugin(tauri_plugin_updater::Builder::new().build())
     .plugin(tauri_plugin_opener::init())
+    .plugin(tauri_plugin_global_shortcut::Builder::new().build())
 
     // menubar
     .menu(|app| build_menu(app))
@@ -97,7 +230,8 @@ fn main() -> Result<()> {
 
     // per-navigation hooks
     .on_page_load(|window, payload| {
-      if window.label() == "splash" { return; }
+      // the tray window only shows when its icon is clicked
+      if window.label() == "splash" || window.label() == "tray" { return; }
       match payload.event() {
         PageLoadEvent::Started => {}
         PageLoadEvent::Finished => {
@@ -109,6 +243,8 @@ fn main() -> Result<()> {
     })
 
     .setup(|app| {
+      let conf = ChatConfJson::load();
+
       // reuse existing window or create one
       let _main = if let Some(existing) = any_app_window(&app.handle()) {
         let _ = existing.eval(LOADER_INJECT_JS);
@@ -118,50 +254,78 @@ fn main() -> Result<()> {
         let _ = existing.show();
         existing
       } else {
-        #[cfg(debug_assertions)]
-        let url = WebviewUrl::External("http://localhost:1420".parse().unwrap());
-        #[cfg(not(debug_assertions))]
-        let url = WebviewUrl::External("https://chatgpt.com".parse().unwrap());
-
-        WebviewWindowBuilder::new(app, "core", url)
-          .title("ChatGPT")
-          .resizable(true)
-          .visible(true)
-          .initialization_script(LOADER_INJECT_JS)
-          .initialization_script(LOADER_SHOW_JS)
-          .initialization_script(VIRTUALIZER_JS)
-          .initialization_script(VIRTUALIZER_LOADER_JS)
-          .build()?
+        build_core_window(app, &conf)?
       };
 
       // tray
       let _tray = build_tray(&app.handle())?;
 
-      // Load saved theme and apply at startup (native + React broadcast)
-      let saved = ChatConfJson::load().theme.to_lowercase();
-      let native = match saved.as_str() {
-        "dark" => Some(Theme::Dark),
-        "light" => Some(Theme::Light),
-        _ => None, // system
-      };
-      for w in app.webview_windows().values() {
-        let _ = w.set_theme(native);
-        let _ = w.emit("menu-set-theme", &saved);
+      // the webviews may only touch the active profile's dirs
+      for dir in [utils::chat_config_dir(), utils::chat_root(), utils::chat_state_dir(), utils::chat_cache_dir()] {
+        app.fs_scope().allow_directory(&dir, true)?;
       }
 
+      // Apply saved settings at startup, then follow chat.conf.json for live changes
+      watch::init(app.handle(), conf);
+      scheduler::init(app.handle());
+      sources::watch_dirs();
+
       Ok(())
     })
 
     // commands
     .invoke_handler(tauri::generate_handler![
       get_chat_conf,
+      get_chat_conf_path,
+      get_effective_conf,
       set_chat_conf,
+      patch_chat_conf,
       reset_chat_conf,
+      list_conf_backups,
+      restore_conf_backup,
+      export_bundle,
+      preview_bundle,
+      import_bundle,
+      import_openai_export,
+      list_profiles,
+      create_profile,
+      open_profile,
+      sync_prompts,
+      sync_user_prompts,
+      set_online,
+      parse_prompt,
+      cmd_list,
+      get_chat_model_cmd,
+      prompt_vars,
+      render_prompt,
+      search_prompts,
+      list_sync_history,
+      diff_sync,
+      rollback_sync,
+      download,
+      save_file,
+      download_list,
+      export_markdown,
+      export_html,
+      export_pdf,
+      fs_extra::metadata,
+      get_chat_root,
+      window_reload,
       open_external,
       set_theme_all,
       run_check_update,
       get_app_info,
     ])
 
-    .run(tauri::generate_context!())
+    .build(context)?
+    .run(|_app, event| {
+      // closing a window to rebuild it must not quit the app
+      if let RunEvent::ExitRequested { code: None, api, .. } = event
+        && watch::is_rebuilding()
+      {
+        api.prevent_exit();
+      }
+    });
+
+  Ok(())
 }
\ No newline at end of file
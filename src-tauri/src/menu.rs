@@ -1,9 +1,13 @@
 use tauri::{
-  menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu},
+  menu::{CheckMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu},
   AppHandle, Emitter, Manager, Runtime, Theme, WebviewUrl, WebviewWindowBuilder,
 };
 use tauri_plugin_updater::UpdaterExt;
 
+use crate::conf::Theme as ConfTheme;
+use crate::profile;
+use crate::utils::set_theme_all;
+
 // ───────────── helpers ─────────────
 
 fn open_or_focus_config<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
@@ -28,12 +32,6 @@ fn open_or_focus_config<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
   Ok(())
 }
 
-fn apply_theme_to_all<R: Runtime>(app: &AppHandle<R>, theme: Theme) {
-  for w in app.webview_windows().values() {
-    let _ = w.set_theme(Some(theme));
-  }
-}
-
 // ───────────── UI: menu structure ─────────────
 
 pub fn build_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<Menu<R>> {
@@ -53,6 +51,15 @@ pub fn build_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<Menu<R>> {
 
   ])?;
 
+  // Profiles: each one runs in its own window with its own data dir and session
+  let profiles = Submenu::new(app, "Profiles", true)?;
+  for name in profile::list() {
+    let id = format!("profile-open:{name}");
+    profiles.append(&CheckMenuItem::with_id(app, id, &name, true, name == profile::active(), None::<&str>)?)?;
+  }
+  profiles.append(&PredefinedMenuItem::separator(app)?)?;
+  profiles.append(&MenuItem::with_id(app, "profile-manage", "Manage Profiles…", true, None::<&str>)?)?;
+
   // View
   let view = Submenu::with_items(app, "View", true, &[
     &MenuItem::with_id(app, "toggle-darkmode", "Toggle Dark Mode", true, None::<&str>)?,
@@ -66,12 +73,12 @@ pub fn build_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<Menu<R>> {
     &MenuItem::with_id(app, "help-report-bug",  "Report Bug",   true, None::<&str>)?,
   ])?;
 
-  Menu::with_items(app, &[&chatgpt, &preferences, &view, &help])
+  Menu::with_items(app, &[&chatgpt, &preferences, &profiles, &view, &help])
 }
 
 // ───────────── handlers ─────────────
 
-pub fn handle_menu_event<R: Runtime>(app: &AppHandle<R>, event: MenuEvent) {
+pub fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
   match event.id().as_ref() {
     "check-updates" => {
       let appc = app.clone();
@@ -103,18 +110,36 @@ pub fn handle_menu_event<R: Runtime>(app: &AppHandle<R>, event: MenuEvent) {
       });
     }
 
-    "pref-open-config" => {
+    "pref-open-config" | "profile-manage" => {
       let _ = open_or_focus_config(app);
     }
 
+    id if id.starts_with("profile-open:") => {
+      let name = &id["profile-open:".len()..];
+      if name == profile::active() {
+        if let Some(core) = app.get_webview_window("core") {
+          let _ = core.show();
+          let _ = core.set_focus();
+        }
+      } else if let Err(e) = profile::open(name) {
+        let _ = app.emit("notice", format!("Failed to open profile {name}: {e}"));
+      }
+      // keep the check mark on the running profile
+      if let Ok(menu) = build_menu(app) {
+        let _ = app.set_menu(menu);
+      }
+    }
+
     "toggle-darkmode" => {
       let current = app.get_webview_window("core").and_then(|w| w.theme().ok());
       let next = match current {
-        Some(Theme::Dark) => Theme::Light,
-        _ => Theme::Dark,
+        Some(Theme::Dark) => ConfTheme::Light,
+        _ => ConfTheme::Dark,
       };
-      apply_theme_to_all(app, next);
-      let _ = app.emit("menu-toggle-dark", ());
+      // saved like any other theme change, so a reload keeps it
+      if let Err(e) = set_theme_all(app.clone(), next) {
+        let _ = app.emit("notice", format!("Failed to switch the theme: {e}"));
+      }
     }
 
    /*
@@ -0,0 +1,150 @@
+// src-tauri/src/overrides.rs  — CHATGPT_* env vars, `--set key=value` and policy layered over chat.conf.json
+
+use std::{collections::BTreeMap, sync::OnceLock};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::conf::ChatConfJson;
+use crate::policy::{self, POLICY_PATH};
+
+pub const ENV_PREFIX: &str = "CHATGPT_";
+
+static CLI: OnceLock<Vec<(String, String)>> = OnceLock::new();
+
+/// Where an effective value came from. Later layers win:
+/// default < file < env < cli < policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfSource {
+  Default,
+  File,
+  Env,
+  Cli,
+  Policy,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldSource {
+  pub source: ConfSource,
+  /// Env var name, `--set` argument or policy file that supplied the value.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub origin: Option<String>,
+}
+
+/// Payload of `get_effective_conf`.
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectiveConf {
+  pub conf: ChatConfJson,
+  pub sources: BTreeMap<String, FieldSource>,
+  /// Overrides that were ignored because the value did not validate.
+  pub rejected: Vec<String>,
+}
+
+// ---------- CLI (picked once, from `--set key=value`) ----------
+pub fn init<I: IntoIterator<Item = String>>(args: I) {
+  let mut args = args.into_iter();
+  let mut sets = vec![];
+  while let Some(arg) = args.next() {
+    let pair = if arg == "--set" {
+      args.next()
+    } else {
+      arg.strip_prefix("--set=").map(str::to_string)
+    };
+    let Some(pair) = pair else { continue };
+    match pair.split_once('=') {
+      Some((key, value)) => sets.push((key.trim().to_string(), value.to_string())),
+      None => eprintln!("Ignoring malformed --set {pair}, expected key=value"),
+    }
+  }
+  let _ = CLI.set(sets);
+}
+
+// ---------- Layering ----------
+/// Field names that can be overridden (everything but `schema_version`).
+fn fields() -> Vec<String> {
+  match serde_json::to_value(ChatConfJson::defaults()) {
+    Ok(Value::Object(map)) => map.into_iter().map(|(k, _)| k).filter(|k| k != "schema_version").collect(),
+    _ => vec![],
+  }
+}
+
+/// Collects overrides in precedence order, so later entries replace earlier ones.
+fn collect() -> Vec<(String, String, FieldSource)> {
+  let fields = fields();
+  let mut out = vec![];
+
+  for field in &fields {
+    let var = format!("{ENV_PREFIX}{}", field.to_uppercase());
+    if let Ok(raw) = std::env::var(&var) {
+      out.push((field.clone(), raw, FieldSource { source: ConfSource::Env, origin: Some(var) }));
+    }
+  }
+
+  for (key, raw) in CLI.get().into_iter().flatten() {
+    if !fields.contains(key) {
+      eprintln!("Ignoring --set {key}: not a config field");
+      continue;
+    }
+    let origin = Some(format!("--set {key}={raw}"));
+    out.push((key.clone(), raw.clone(), FieldSource { source: ConfSource::Cli, origin }));
+  }
+  out
+}
+
+/// Turns a raw string into the JSON type the field expects, e.g. `1`/`yes`/`on`
+/// for booleans or `a,b` for lists; strings are taken verbatim.
+fn coerce(default: &Value, raw: &str) -> Value {
+  match default {
+    Value::Bool(_) => match raw.trim().to_ascii_lowercase().as_str() {
+      "1" | "true" | "yes" | "on" => Value::Bool(true),
+      "0" | "false" | "no" | "off" => Value::Bool(false),
+      _ => Value::String(raw.into()),
+    },
+    Value::Number(_) => serde_json::from_str(raw.trim()).unwrap_or_else(|_| Value::String(raw.into())),
+    // `user,awesome` as well as `["user","awesome"]`
+    Value::Array(_) => serde_json::from_str(raw.trim())
+      .unwrap_or_else(|_| raw.split(',').map(|v| Value::String(v.trim().into())).collect()),
+    _ => Value::String(raw.into()),
+  }
+}
+
+/// Layers env and CLI overrides, then the managed policy, over `base` (the file, or defaults when the
+/// file is unusable). Invalid overrides are dropped with a warning instead of
+/// failing startup.
+pub fn apply(base: ChatConfJson, file_ok: bool) -> EffectiveConf {
+  let defaults = serde_json::to_value(ChatConfJson::defaults()).unwrap_or_default();
+  let mut value = serde_json::to_value(&base).unwrap_or_default();
+  let base_source = if file_ok { ConfSource::File } else { ConfSource::Default };
+
+  let mut sources: BTreeMap<String, FieldSource> =
+    fields().into_iter().map(|f| (f, FieldSource { source: base_source, origin: None })).collect();
+  let mut rejected = vec![];
+  for (field, raw, source) in collect() {
+    let v = coerce(&defaults[&field], &raw);
+    let mut probe = Map::new();
+    probe.insert(field.clone(), v.clone());
+    if let Err(e) = ChatConfJson::validate(Value::Object(probe)) {
+      eprintln!("Ignoring {}: {e}", source.origin.as_deref().unwrap_or(&field));
+      rejected.extend(source.origin);
+      continue;
+    }
+    value[&field] = v;
+    sources.insert(field, source);
+  }
+
+  // admin-locked fields win over everything the user can set
+  for (field, v) in policy::load() {
+    value[&field] = v;
+    sources.insert(field, FieldSource { source: ConfSource::Policy, origin: Some(POLICY_PATH.into()) });
+  }
+
+  let conf = serde_json::from_value(value).unwrap_or(base);
+  EffectiveConf { conf, sources, rejected }
+}
+
+// ---------- Tauri commands ----------
+#[tauri::command]
+pub fn get_effective_conf() -> EffectiveConf {
+  ChatConfJson::effective()
+}
@@ -0,0 +1,448 @@
+// src-tauri/src/parser.rs  — tolerant prompt file parsing (CSV / JSON / Markdown dir) with row-level warnings
+
+use std::{
+  collections::HashMap,
+  fs,
+  path::{Path, PathBuf},
+};
+
+use csv::{Position, ReaderBuilder, StringRecord};
+use encoding_rs::{Encoding, UTF_8};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use walkdir::WalkDir;
+
+use crate::prompts::{gen_cmd, PromptRecord};
+
+/// A quoted field running past this many lines is taken for a stray quote.
+const MAX_RECORD_LINES: u64 = 50;
+const DELIMITERS: &[u8] = b",;\t|";
+/// Bytes looked at when guessing the delimiter.
+const SNIFF_BYTES: usize = 8 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParseWarning {
+  /// 1-based line for CSV, 1-based entry for JSON and for the files of a dir.
+  pub line: u64,
+  pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParsedPrompts {
+  pub records: Vec<PromptRecord>,
+  pub warnings: Vec<ParseWarning>,
+  pub encoding: String,
+  pub delimiter: String,
+}
+
+// ---------- Encoding + delimiter ----------
+/// Honours a UTF-8/UTF-16 BOM, then tries UTF-8, then guesses (e.g. Windows-1252 exports).
+fn decode(bytes: &[u8]) -> (String, &'static Encoding) {
+  if let Some((encoding, bom)) = Encoding::for_bom(bytes) {
+    let (text, _) = encoding.decode_without_bom_handling(&bytes[bom..]);
+    return (text.into_owned(), encoding);
+  }
+  if let Ok(text) = std::str::from_utf8(bytes) {
+    return (text.to_string(), UTF_8);
+  }
+  let mut detector = chardetng::EncodingDetector::new();
+  detector.feed(bytes, true);
+  let encoding = detector.guess(None, true);
+  let (text, _, _) = encoding.decode(bytes);
+  (text.into_owned(), encoding)
+}
+
+/// Picks the candidate that splits the first records into the same, non-zero
+/// number of fields most consistently. Quoted text is skipped.
+fn sniff_delimiter(text: &str) -> u8 {
+  // back off to a char boundary before slicing, or a multibyte char there panics
+  let mut n = text.len().min(SNIFF_BYTES);
+  while !text.is_char_boundary(n) {
+    n -= 1;
+  }
+  let head = &text[..n];
+
+  let score = |delimiter: u8| {
+    let (mut counts, mut current, mut quoted) = (vec![], 0usize, false);
+    for b in head.bytes() {
+      match b {
+        b'"' => quoted = !quoted,
+        b'\n' if !quoted => {
+          if current > 0 {
+            counts.push(current);
+          }
+          current = 0;
+        }
+        b if b == delimiter && !quoted => current += 1,
+        _ => {}
+      }
+    }
+    let Some(&first) = counts.first() else { return (0, 0) };
+    let consistent = counts.iter().filter(|&&c| c == first).count();
+    (consistent, first)
+  };
+
+  DELIMITERS.iter().copied().max_by_key(|d| score(*d)).filter(|d| score(*d).0 > 0).unwrap_or(b',')
+}
+
+// ---------- Rows ----------
+/// A multi-line record that never closes its quote (it ran into EOF) or that
+/// spans an implausible number of lines is a stray quote, not a long prompt.
+fn swallowed(text: &str, start: &Position, end: &Position) -> bool {
+  let lines = end.line() - start.line();
+  if lines <= 1 {
+    return false;
+  }
+  let raw = &text[start.byte() as usize..(end.byte() as usize).min(text.len())];
+  lines > MAX_RECORD_LINES || raw.matches('"').count() % 2 == 1
+}
+
+/// Reads every row with its 1-based line. A record whose quotes swallow the
+/// rest of the file is re-read as a single literal line instead.
+fn read_rows(text: &str, delimiter: u8, warnings: &mut Vec<ParseWarning>) -> Vec<(u64, StringRecord)> {
+  let builder = {
+    let mut b = ReaderBuilder::new();
+    b.has_headers(false).flexible(true).delimiter(delimiter);
+    b
+  };
+
+  let mut rows = vec![];
+  let (mut offset, mut line_base) = (0usize, 1u64);
+  loop {
+    let mut rdr = builder.from_reader(&text.as_bytes()[offset..]);
+    let mut record = StringRecord::new();
+    let mut restart: Option<(Position, String)> = None;
+    loop {
+      let start = rdr.position().clone();
+      match rdr.read_record(&mut record) {
+        Ok(false) => break,
+        Ok(true) if swallowed(&text[offset..], &start, rdr.position()) => {
+          restart = Some((start, "unbalanced quote, read the line literally".into()));
+          break;
+        }
+        Ok(true) => rows.push((line_base + start.line() - 1, record.clone())),
+        Err(e) => {
+          restart = Some((start, format!("{e}, read the line literally")));
+          break;
+        }
+      }
+    }
+
+    let Some((start, reason)) = restart else { break };
+    let from = offset + start.byte() as usize;
+    let to = text[from..].find('\n').map(|i| from + i + 1).unwrap_or(text.len());
+    let line = line_base + start.line() - 1;
+    warnings.push(ParseWarning { line, reason });
+
+    let literal = text[from..to]
+      .trim_end_matches(['\r', '\n'])
+      .split(delimiter as char)
+      .map(|f| f.trim().trim_matches('"'))
+      .collect::<StringRecord>();
+    rows.push((line, literal));
+    offset = to;
+    line_base = line + 1;
+    if offset >= text.len() {
+      break;
+    }
+  }
+  rows
+}
+
+// ---------- Records ----------
+struct Columns {
+  cmd: Option<usize>,
+  act: usize,
+  prompt: usize,
+  width: usize,
+}
+
+/// A first row naming an `act` column is a header; otherwise three or more
+/// columns are `cmd,act,prompt` and two are `act,prompt`.
+fn columns(first: &StringRecord) -> (Columns, bool) {
+  let named = |name: &str| first.iter().position(|c| c.trim().eq_ignore_ascii_case(name));
+  match named("act") {
+    Some(act) => {
+      let prompt = named("prompt").unwrap_or(act + 1);
+      (Columns { cmd: named("cmd"), act, prompt, width: first.len() }, true)
+    }
+    None if first.len() >= 3 => (Columns { cmd: Some(0), act: 1, prompt: 2, width: 3 }, false),
+    None => (Columns { cmd: None, act: 0, prompt: 1, width: 2 }, false),
+  }
+}
+
+/// Shared by CSV and JSON: trims, derives or slugifies `cmd`, and drops
+/// empty rows and duplicate commands with a warning.
+struct Collector {
+  tag: String,
+  seen: HashMap<String, u64>,
+  records: Vec<PromptRecord>,
+  warnings: Vec<ParseWarning>,
+}
+
+impl Collector {
+  fn new(tag: &str, warnings: Vec<ParseWarning>) -> Self {
+    Collector { tag: tag.into(), seen: HashMap::new(), records: vec![], warnings }
+  }
+
+  fn warn(&mut self, line: u64, reason: impl Into<String>) {
+    self.warnings.push(ParseWarning { line, reason: reason.into() });
+  }
+
+  fn push(&mut self, line: u64, cmd: Option<&str>, act: Option<&str>, prompt: Option<&str>) {
+    let act = act.map(str::trim).unwrap_or_default();
+    let prompt = prompt.map(str::trim).unwrap_or_default();
+    if act.is_empty() && prompt.is_empty() {
+      return; // blank line
+    }
+    if act.is_empty() || prompt.is_empty() {
+      self.warn(line, format!("missing {}, row skipped", if act.is_empty() { "act" } else { "prompt" }));
+      return;
+    }
+
+    let cmd = match cmd.map(str::trim).filter(|c| !c.is_empty()) {
+      Some(raw) => {
+        let slug = gen_cmd(raw);
+        if slug != raw {
+          self.warn(line, format!("command \"{raw}\" renamed to \"{slug}\""));
+        }
+        slug
+      }
+      None => gen_cmd(act),
+    };
+    if cmd.is_empty() {
+      self.warn(line, format!("\"{act}\" has no usable command name, row skipped"));
+      return;
+    }
+    if let Some(first) = self.seen.get(&cmd) {
+      let first = *first;
+      self.warn(line, format!("duplicate command /{cmd} (first on line {first}), row skipped"));
+      return;
+    }
+
+    self.seen.insert(cmd.clone(), line);
+    self.records.push(PromptRecord {
+      cmd,
+      act: act.into(),
+      prompt: prompt.into(),
+      tags: vec![self.tag.clone()],
+      enable: true,
+    });
+  }
+}
+
+// ---------- Entry points ----------
+pub fn parse_csv_text(text: &str, tag: &str) -> ParsedPrompts {
+  parse_decoded(text.trim_start_matches('\u{feff}'), UTF_8, tag)
+}
+
+pub fn parse_csv(bytes: &[u8], tag: &str) -> ParsedPrompts {
+  let (text, encoding) = decode(bytes);
+  parse_decoded(text.trim_start_matches('\u{feff}'), encoding, tag)
+}
+
+fn parse_decoded(text: &str, encoding: &'static Encoding, tag: &str) -> ParsedPrompts {
+  let delimiter = sniff_delimiter(text);
+  let mut warnings = vec![];
+  let rows = read_rows(text, delimiter, &mut warnings);
+
+  let mut collector = Collector::new(tag, warnings);
+  if let Some((_, first)) = rows.first() {
+    let (cols, has_header) = columns(first);
+    for (line, row) in rows.iter().skip(has_header as usize) {
+      if row.len() > cols.width && row.iter().skip(cols.width).any(|f| !f.trim().is_empty()) {
+        collector.warn(*line, format!("{} extra columns ignored", row.len() - cols.width));
+      }
+      collector.push(*line, cols.cmd.and_then(|i| row.get(i)), row.get(cols.act), row.get(cols.prompt));
+    }
+  }
+
+  ParsedPrompts {
+    records: collector.records,
+    warnings: collector.warnings,
+    encoding: encoding.name().into(),
+    delimiter: (delimiter as char).to_string(),
+  }
+}
+
+/// `[{ cmd?, act, prompt }]`
+pub fn parse_json(bytes: &[u8], tag: &str) -> Result<ParsedPrompts, String> {
+  let (text, encoding) = decode(bytes);
+  let rows: Vec<Value> =
+    serde_json::from_str(text.trim_start_matches('\u{feff}')).map_err(|e| format!("invalid JSON: {e}"))?;
+
+  let mut collector = Collector::new(tag, vec![]);
+  for (i, row) in rows.iter().enumerate() {
+    let field = |name: &str| row.get(name).and_then(Value::as_str);
+    collector.push(i as u64 + 1, field("cmd"), field("act"), field("prompt"));
+  }
+  Ok(ParsedPrompts {
+    records: collector.records,
+    warnings: collector.warnings,
+    encoding: encoding.name().into(),
+    delimiter: String::new(),
+  })
+}
+
+// ---------- Markdown directories ----------
+/// YAML between the leading `---` lines of a prompt file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct FrontMatter {
+  cmd: Option<String>,
+  act: Option<String>,
+  tags: Vec<String>,
+  enable: Option<bool>,
+}
+
+/// Splits `---\n<yaml>\n---\n<body>`; text without an opening `---` line is all body.
+fn split_front_matter(text: &str) -> Result<(Option<&str>, &str), String> {
+  let Some(rest) = text.strip_prefix("---\n").or_else(|| text.strip_prefix("---\r\n")) else {
+    return Ok((None, text));
+  };
+  let mut offset = 0;
+  for line in rest.split_inclusive('\n') {
+    if matches!(line.trim_end(), "---" | "...") {
+      return Ok((Some(&rest[..offset]), &rest[offset + line.len()..]));
+    }
+    offset += line.len();
+  }
+  Err("front matter is never closed by a --- line".into())
+}
+
+/// One prompt per `.md` file, in path order. `act` defaults to the file name,
+/// front matter `tags` are added to `tag`, and hidden entries (`.git`) are skipped.
+pub fn parse_dir(dir: &Path, tag: &str) -> Result<ParsedPrompts, String> {
+  if !dir.is_dir() {
+    return Err(format!("{}: not a directory", dir.display()));
+  }
+  let files = WalkDir::new(dir)
+    .sort_by_file_name()
+    .into_iter()
+    .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+    .filter_map(Result::ok)
+    .filter(|e| e.file_type().is_file())
+    .filter(|e| e.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("md")));
+
+  let mut collector = Collector::new(tag, vec![]);
+  for (i, file) in files.enumerate() {
+    let entry = i as u64 + 1;
+    let name = file.path().strip_prefix(dir).unwrap_or(file.path()).display().to_string();
+    let bytes = match fs::read(file.path()) {
+      Ok(bytes) => bytes,
+      Err(e) => {
+        collector.warn(entry, format!("{name}: {e}, file skipped"));
+        continue;
+      }
+    };
+    let (text, _) = decode(&bytes);
+    let text = text.trim_start_matches('\u{feff}');
+
+    let parsed = split_front_matter(text).and_then(|(yaml, body)| {
+      let meta = match yaml.filter(|y| !y.trim().is_empty()) {
+        Some(yaml) => serde_yaml::from_str::<FrontMatter>(yaml).map_err(|e| format!("front matter: {e}"))?,
+        None => FrontMatter::default(),
+      };
+      Ok((meta, body))
+    });
+    let (meta, body) = match parsed {
+      Ok(parsed) => parsed,
+      Err(e) => {
+        collector.warn(entry, format!("{name}: {e}, file skipped"));
+        continue;
+      }
+    };
+
+    let stem = file.path().file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+    let (records, warnings) = (collector.records.len(), collector.warnings.len());
+    collector.push(entry, meta.cmd.as_deref(), Some(meta.act.as_deref().unwrap_or(&stem)), Some(body));
+    for w in &mut collector.warnings[warnings..] {
+      w.reason = format!("{name}: {}", w.reason);
+    }
+    if let Some(record) = collector.records.get_mut(records) {
+      for t in meta.tags {
+        if !record.tags.contains(&t) {
+          record.tags.push(t);
+        }
+      }
+      record.enable = meta.enable.unwrap_or(true);
+    }
+  }
+
+  Ok(ParsedPrompts {
+    records: collector.records,
+    warnings: collector.warnings,
+    encoding: String::new(),
+    delimiter: String::new(),
+  })
+}
+
+// ---------- Tauri commands ----------
+/// Parses a prompt CSV given either as text or as a file (which enables
+/// encoding detection). Nothing is written.
+#[tauri::command]
+pub fn parse_prompt(data: Option<String>, path: Option<PathBuf>, tag: Option<String>) -> Result<ParsedPrompts, String> {
+  let tag = tag.as_deref().unwrap_or("user-sync");
+  match (path, data) {
+    (Some(path), _) => {
+      let bytes = fs::read(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+      Ok(parse_csv(&bytes, tag))
+    }
+    (None, Some(data)) => Ok(parse_csv_text(&data, tag)),
+    (None, None) => Err("either data or path is required".into()),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn sniff_delimiter_with_multibyte_char_at_the_cut() {
+    // pad so the 3-byte `’` starts one byte before SNIFF_BYTES
+    let row = "act;prompt\n";
+    let mut text = row.repeat((SNIFF_BYTES - 1) / row.len());
+    text.push_str(&"x".repeat(SNIFF_BYTES - 1 - text.len()));
+    text.push_str("’;y\n");
+    assert!(!text.is_char_boundary(SNIFF_BYTES));
+    assert_eq!(sniff_delimiter(&text), b';');
+  }
+
+  fn acts(parsed: &ParsedPrompts) -> Vec<&str> {
+    parsed.records.iter().map(|r| r.act.as_str()).collect()
+  }
+
+  fn warnings(parsed: &ParsedPrompts) -> Vec<(u64, &str)> {
+    parsed.warnings.iter().map(|w| (w.line, w.reason.as_str())).collect()
+  }
+
+  #[test]
+  fn bom_does_not_hide_the_header() {
+    let parsed = parse_csv(b"\xef\xbb\xbfact,prompt\nPoet,Write a poem\n", "t");
+    assert_eq!(acts(&parsed), ["Poet"]);
+    assert!(parsed.warnings.is_empty());
+    assert_eq!(parsed.encoding, "UTF-8");
+  }
+
+  #[test]
+  fn stray_quote_is_read_literally() {
+    let parsed = parse_csv_text("act,prompt\n\"Poet,Write a poem\nChef,Cook\nDog,Bark\n", "t");
+    assert_eq!(acts(&parsed), ["Poet", "Chef", "Dog"]);
+    assert_eq!(warnings(&parsed), [(2, "unbalanced quote, read the line literally")]);
+  }
+
+  #[test]
+  fn extra_columns_are_ignored_with_a_warning() {
+    let parsed = parse_csv_text("act,prompt\nPoet,Write a poem,extra\nChef,Cook,\n", "t");
+    assert_eq!(acts(&parsed), ["Poet", "Chef"]);
+    assert_eq!(parsed.records[0].prompt, "Write a poem");
+    assert_eq!(warnings(&parsed), [(2, "1 extra columns ignored")]);
+  }
+
+  #[test]
+  fn duplicate_command_keeps_the_first_row() {
+    let parsed = parse_csv_text("cmd,act,prompt\npoet,Poet,A\npoet,Poet Two,B\n", "t");
+    assert_eq!(acts(&parsed), ["Poet"]);
+    assert_eq!(warnings(&parsed), [(3, "duplicate command /poet (first on line 2), row skipped")]);
+  }
+}
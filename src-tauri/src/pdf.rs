@@ -0,0 +1,874 @@
+// src-tauri/src/pdf.rs  — text-based PDF export of a conversation: title page, wrapped text, code blocks, page numbers
+
+use std::{
+  cell::RefCell,
+  collections::{BTreeSet, HashMap},
+  fs,
+  path::PathBuf,
+  sync::OnceLock,
+};
+
+use printpdf::path::PaintMode;
+use printpdf::{Color, IndirectFontRef, Line, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference, Point, Rect, Rgb};
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+use serde::Serialize;
+
+use crate::conf::now_ms;
+use crate::export::{self, Conversation};
+use crate::storage::{self, FileEntry};
+use crate::utils::chat_root;
+
+// A4, in mm
+const PAGE_W: f32 = 210.0;
+const PAGE_H: f32 = 297.0;
+const MARGIN: f32 = 20.0;
+/// Space kept free at the bottom of each page for its number.
+const FOOTER: f32 = 8.0;
+const MM_PER_PT: f32 = 0.352_778;
+
+const BODY_SIZE: f32 = 10.5;
+const CODE_SIZE: f32 = 8.5;
+const LEADING: f32 = 1.45;
+const LIST_INDENT: f32 = 6.0;
+const QUOTE_INDENT: f32 = 5.0;
+
+const TEXT: (f32, f32, f32) = (0.12, 0.14, 0.16);
+const MUTED: (f32, f32, f32) = (0.42, 0.45, 0.49);
+const CODE_BG: (f32, f32, f32) = (0.95, 0.96, 0.97);
+const RULE: (f32, f32, f32) = (0.82, 0.84, 0.87);
+
+const NOTICE: (f32, f32, f32) = (0.72, 0.16, 0.14);
+
+/// Drawn for a character no available font has a glyph for.
+const MISSING: char = '\u{25a1}';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Face {
+  Regular,
+  Bold,
+  Italic,
+  BoldItalic,
+  Mono,
+}
+
+impl Face {
+  fn styled(bold: bool, italic: bool) -> Face {
+    match (bold, italic) {
+      (true, true) => Face::BoldItalic,
+      (true, false) => Face::Bold,
+      (false, true) => Face::Italic,
+      (false, false) => Face::Regular,
+    }
+  }
+
+  /// Advance of `c` in mm at `size` pt, in the font that will draw it.
+  fn advance(self, c: char, size: f32) -> f32 {
+    match slot(self, c) {
+      Some(slot) => slot.font().advance(c, size),
+      None => Slot::Face(Face::Regular).font().advance(MISSING, size),
+    }
+  }
+
+  fn width(self, text: &str, size: f32) -> f32 {
+    text.chars().map(|c| self.advance(c, size)).sum()
+  }
+}
+
+// ---------- Fonts ----------
+// DejaVu covers Latin, Greek, Cyrillic, Hebrew, Arabic letters, box drawing
+// and most math symbols. printpdf cannot subset fonts, so every font a
+// document uses is embedded whole; they are only added once used.
+static DEJAVU_SANS: &[u8] = include_bytes!("../fonts/DejaVuSans.ttf");
+static DEJAVU_SANS_BOLD: &[u8] = include_bytes!("../fonts/DejaVuSans-Bold.ttf");
+static DEJAVU_SANS_OBLIQUE: &[u8] = include_bytes!("../fonts/DejaVuSans-Oblique.ttf");
+static DEJAVU_SANS_BOLD_OBLIQUE: &[u8] = include_bytes!("../fonts/DejaVuSans-BoldOblique.ttf");
+static DEJAVU_SANS_MONO: &[u8] = include_bytes!("../fonts/DejaVuSansMono.ttf");
+
+/// CJK fonts are too large to bundle, so the first of these the system has
+/// (TrueType outlines only, as printpdf embeds fonts as `FontFile2`) is used
+/// for the characters DejaVu lacks.
+fn fallback_paths() -> Vec<PathBuf> {
+  #[cfg(target_os = "windows")]
+  let (dir, names) = (
+    PathBuf::from(std::env::var("WINDIR").unwrap_or_else(|_| "C:\\Windows".into())).join("Fonts"),
+    ["msyh.ttc", "simsun.ttc", "simhei.ttf", "msgothic.ttc", "malgun.ttf", "seguisym.ttf"].as_slice(),
+  );
+  #[cfg(target_os = "macos")]
+  let (dir, names) = (
+    PathBuf::from("/"),
+    [
+      "System/Library/Fonts/PingFang.ttc",
+      "System/Library/Fonts/STHeiti Light.ttc",
+      "System/Library/Fonts/AppleSDGothicNeo.ttc",
+      "System/Library/Fonts/Supplemental/Arial Unicode.ttf",
+      "Library/Fonts/Arial Unicode.ttf",
+    ]
+    .as_slice(),
+  );
+  #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+  let (dir, names) = (
+    PathBuf::from("/usr/share/fonts"),
+    [
+      "truetype/wqy/wqy-microhei.ttc",
+      "wqy-microhei/wqy-microhei.ttc",
+      "truetype/wqy/wqy-zenhei.ttc",
+      "wqy-zenhei/wqy-zenhei.ttc",
+      "truetype/droid/DroidSansFallbackFull.ttf",
+      "google-droid-sans-fonts/DroidSansFallbackFull.ttf",
+      "truetype/arphic/uming.ttc",
+      "truetype/unfonts-core/UnDotum.ttf",
+    ]
+    .as_slice(),
+  );
+  names.iter().map(|name| dir.join(name)).collect()
+}
+
+struct FontFile {
+  bytes: &'static [u8],
+  face: ttf_parser::Face<'static>,
+}
+
+impl FontFile {
+  fn parse(bytes: &'static [u8]) -> Option<FontFile> {
+    let face = ttf_parser::Face::parse(bytes, 0).ok()?;
+    face.tables().glyf?;
+    Some(FontFile { bytes, face })
+  }
+
+  fn has(&self, c: char) -> bool {
+    self.face.glyph_index(c).is_some_and(|g| g.0 != 0)
+  }
+
+  fn advance(&self, c: char, size: f32) -> f32 {
+    let units = self.face.glyph_index(c).and_then(|g| self.face.glyph_hor_advance(g)).unwrap_or(0);
+    units as f32 / self.face.units_per_em() as f32 * size * MM_PER_PT
+  }
+}
+
+/// First face of a TrueType collection as a standalone font; other files are
+/// returned as they are.
+fn sfnt(bytes: Vec<u8>) -> Option<Vec<u8>> {
+  if !bytes.starts_with(b"ttcf") {
+    return Some(bytes);
+  }
+  let u16_at = |at: usize| Some(u16::from_be_bytes(bytes.get(at..at + 2)?.try_into().ok()?));
+  let u32_at = |at: usize| Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?) as usize);
+  let font = u32_at(12)?;
+  let tables = u16_at(font + 4)? as usize;
+  let mut out = bytes.get(font..font + 12 + 16 * tables)?.to_vec();
+  for i in 0..tables {
+    let record = font + 12 + 16 * i;
+    let (offset, length) = (u32_at(record + 8)?, u32_at(record + 12)?);
+    let at = out.len() as u32;
+    out[12 + 16 * i + 8..12 + 16 * i + 12].copy_from_slice(&at.to_be_bytes());
+    out.extend_from_slice(bytes.get(offset..offset + length)?);
+    out.resize(out.len().next_multiple_of(4), 0);
+  }
+  Some(out)
+}
+
+/// Tables kept in an embedded font; layout tables (GSUB, GPOS, kern) are
+/// not used by PDF viewers.
+const KEEP_TABLES: [&[u8; 4]; 13] =
+  [b"OS/2", b"cmap", b"cvt ", b"fpgm", b"glyf", b"head", b"hhea", b"hmtx", b"loca", b"maxp", b"name", b"post", b"prep"];
+
+/// `font` with the outlines of every glyph not needed for `chars` emptied.
+/// Glyph ids and metrics stay as they are, so nothing else has to be remapped.
+fn subset(font: &FontFile, chars: &BTreeSet<char>) -> Option<Vec<u8>> {
+  let bytes = font.bytes;
+  let u16_at = |b: &[u8], at: usize| Some(u16::from_be_bytes(b.get(at..at + 2)?.try_into().ok()?));
+  let u32_at = |b: &[u8], at: usize| Some(u32::from_be_bytes(b.get(at..at + 4)?.try_into().ok()?));
+
+  let mut tables: Vec<([u8; 4], Vec<u8>)> = vec![];
+  for i in 0..u16_at(bytes, 4)? as usize {
+    let record = 12 + 16 * i;
+    let tag: [u8; 4] = bytes.get(record..record + 4)?.try_into().ok()?;
+    let (offset, length) = (u32_at(bytes, record + 8)? as usize, u32_at(bytes, record + 12)? as usize);
+    if KEEP_TABLES.contains(&&tag) {
+      tables.push((tag, bytes.get(offset..offset + length)?.to_vec()));
+    }
+  }
+  let table = |tag: &[u8; 4]| tables.iter().position(|(t, _)| t == tag);
+  let (head, loca, glyf, post) = (table(b"head")?, table(b"loca")?, table(b"glyf")?, table(b"post"));
+  let glyphs = font.face.number_of_glyphs() as usize;
+  let long = u16_at(&tables[head].1, 50)? == 1;
+  let offset = |i: usize| match long {
+    true => u32_at(&tables[loca].1, i * 4).map(|o| o as usize),
+    false => u16_at(&tables[loca].1, i * 2).map(|o| o as usize * 2),
+  };
+
+  // glyph 0 is .notdef; composite glyphs pull in their components
+  let mut used = vec![false; glyphs];
+  let mut todo: Vec<u16> = std::iter::once(0).chain(chars.iter().filter_map(|c| font.face.glyph_index(*c)).map(|g| g.0)).collect();
+  while let Some(g) = todo.pop() {
+    let g = g as usize;
+    if g >= glyphs || std::mem::replace(&mut used[g], true) {
+      continue;
+    }
+    let data = tables[glyf].1.get(offset(g)?..offset(g + 1)?)?;
+    if data.len() < 10 || (u16_at(data, 0)? as i16) >= 0 {
+      continue;
+    }
+    let mut at = 10;
+    loop {
+      let flags = u16_at(data, at)?;
+      todo.push(u16_at(data, at + 2)?);
+      at += 4 + if flags & 0x1 != 0 { 4 } else { 2 };
+      at += match flags {
+        f if f & 0x8 != 0 => 2,
+        f if f & 0x40 != 0 => 4,
+        f if f & 0x80 != 0 => 8,
+        _ => 0,
+      };
+      if flags & 0x20 == 0 {
+        break;
+      }
+    }
+  }
+
+  let (mut new_glyf, mut new_loca) = (vec![], vec![]);
+  for (g, used) in used.iter().enumerate() {
+    new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+    if *used {
+      new_glyf.extend_from_slice(tables[glyf].1.get(offset(g)?..offset(g + 1)?)?);
+      new_glyf.resize(new_glyf.len().next_multiple_of(4), 0);
+    }
+  }
+  new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+  tables[glyf].1 = new_glyf;
+  tables[loca].1 = new_loca;
+  // loca is written in the long format; the whole-font checksum is left to viewers
+  tables[head].1.get_mut(50..52)?.copy_from_slice(&1u16.to_be_bytes());
+  tables[head].1.get_mut(8..12)?.copy_from_slice(&[0; 4]);
+  // post 3.0 drops the glyph names, which are only useful for PostScript
+  if let Some(post) = post {
+    tables[post].1.truncate(32);
+    tables[post].1.get_mut(..4)?.copy_from_slice(&0x0003_0000u32.to_be_bytes());
+  }
+
+  let count = tables.len();
+  let power = 1 << count.ilog2();
+  let mut out = bytes.get(..4)?.to_vec();
+  for field in [count, power * 16, count.ilog2() as usize, (count - power) * 16] {
+    out.extend_from_slice(&(field as u16).to_be_bytes());
+  }
+  let mut at = 12 + 16 * count;
+  for (tag, data) in &tables {
+    let checksum = data.chunks(4).fold(0u32, |sum, chunk| {
+      let mut word = [0; 4];
+      word[..chunk.len()].copy_from_slice(chunk);
+      sum.wrapping_add(u32::from_be_bytes(word))
+    });
+    out.extend_from_slice(tag);
+    for field in [checksum, at as u32, data.len() as u32] {
+      out.extend_from_slice(&field.to_be_bytes());
+    }
+    at += data.len().next_multiple_of(4);
+  }
+  for (_, data) in &tables {
+    out.extend_from_slice(data);
+    out.resize(out.len().next_multiple_of(4), 0);
+  }
+  Some(out)
+}
+
+fn bundled(face: Face) -> &'static FontFile {
+  static FONTS: OnceLock<[FontFile; 5]> = OnceLock::new();
+  let fonts = FONTS.get_or_init(|| {
+    [DEJAVU_SANS, DEJAVU_SANS_BOLD, DEJAVU_SANS_OBLIQUE, DEJAVU_SANS_BOLD_OBLIQUE, DEJAVU_SANS_MONO]
+      .map(|bytes| FontFile::parse(bytes).expect("bundled fonts are TrueType"))
+  });
+  &fonts[face as usize]
+}
+
+/// Read on first use and kept for the life of the process.
+fn fallbacks() -> &'static [FontFile] {
+  static FONTS: OnceLock<Vec<FontFile>> = OnceLock::new();
+  FONTS.get_or_init(|| {
+    fallback_paths()
+      .into_iter()
+      .filter_map(|path| sfnt(fs::read(path).ok()?))
+      .filter_map(|bytes| FontFile::parse(Box::leak(bytes.into_boxed_slice())))
+      .collect()
+  })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Slot {
+  Face(Face),
+  Fallback(usize),
+}
+
+impl Slot {
+  fn font(self) -> &'static FontFile {
+    match self {
+      Slot::Face(face) => bundled(face),
+      Slot::Fallback(i) => &fallbacks()[i],
+    }
+  }
+}
+
+/// Font that draws `c` in `face`: the face itself, then DejaVu Sans, then the
+/// system fallbacks; `None` when none of them has a glyph.
+fn slot(face: Face, c: char) -> Option<Slot> {
+  if bundled(face).has(c) {
+    return Some(Slot::Face(face));
+  }
+  if bundled(Face::Regular).has(c) {
+    return Some(Slot::Face(Face::Regular));
+  }
+  fallbacks().iter().position(|f| f.has(c)).map(Slot::Fallback)
+}
+
+/// `c` as drawn: tabs and controls become spaces, invisible format
+/// characters (zero-width joiners, variation selectors) are dropped.
+fn printable(c: char) -> Option<char> {
+  match c {
+    '\t' => Some(' '),
+    '\u{200b}'..='\u{200f}' | '\u{2060}'..='\u{2064}' | '\u{fe00}'..='\u{fe0f}' | '\u{feff}' => None,
+    c if c.is_control() => Some(' '),
+    c => Some(c),
+  }
+}
+
+/// Characters of `text` no available font can draw.
+fn missing_chars(text: &str, missing: &mut BTreeSet<char>) {
+  for c in text.chars().filter(|c| *c != '\n').filter_map(printable) {
+    if slot(Face::Regular, c).is_none() {
+      missing.insert(c);
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+struct Run {
+  face: Face,
+  text: String,
+}
+
+impl Run {
+  fn new(face: Face, text: impl Into<String>) -> Run {
+    Run { face, text: text.into() }
+  }
+}
+
+// ---------- Blocks ----------
+enum Block {
+  Heading(u8, Vec<Run>),
+  Para { runs: Vec<Run>, indent: f32, prefix: Option<String>, quote: bool },
+  Code { text: String, indent: f32 },
+  Table(Vec<Vec<Vec<Run>>>),
+  Rule,
+}
+
+/// Flattens the Markdown of one message into blocks.
+fn blocks(markdown: &str) -> Vec<Block> {
+  let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS | Options::ENABLE_MATH;
+  let mut out = vec![];
+  let (mut bold, mut italic, mut quote) = (0, 0, 0);
+  let mut lists: Vec<Option<u64>> = vec![];
+  let mut runs: Vec<Run> = vec![];
+  let mut prefix: Option<String> = None;
+  let mut code: Option<String> = None;
+  let mut heading: Option<u8> = None;
+  let mut table: Option<Vec<Vec<Vec<Run>>>> = None;
+  let mut links: Vec<String> = vec![];
+
+  let indent = |lists: &Vec<Option<u64>>, quote: usize| lists.len() as f32 * LIST_INDENT + quote as f32 * QUOTE_INDENT;
+  macro_rules! flush {
+    () => {
+      if !runs.is_empty() {
+        let text = std::mem::take(&mut runs);
+        if let Some(level) = heading {
+          out.push(Block::Heading(level, text));
+        } else if text.iter().any(|r| !r.text.trim().is_empty()) {
+          out.push(Block::Para { runs: text, indent: indent(&lists, quote), prefix: prefix.take(), quote: quote > 0 });
+        }
+      }
+    };
+  }
+
+  for event in Parser::new_ext(markdown, options) {
+    let face = Face::styled(bold > 0, italic > 0);
+    match event {
+      Event::Start(Tag::Heading { level, .. }) => {
+        flush!();
+        heading = Some(level as u8);
+      }
+      Event::End(TagEnd::Heading(_)) => {
+        flush!();
+        heading = None;
+      }
+      Event::End(TagEnd::Paragraph) => flush!(),
+      Event::Start(Tag::BlockQuote(_)) => {
+        flush!();
+        quote += 1;
+      }
+      Event::End(TagEnd::BlockQuote(_)) => {
+        flush!();
+        quote -= 1;
+      }
+      Event::Start(Tag::CodeBlock(_)) => {
+        flush!();
+        code = Some(String::new());
+      }
+      Event::End(TagEnd::CodeBlock) => {
+        let text = code.take().unwrap_or_default();
+        if let Some(prefix) = prefix.take() {
+          out.push(Block::Para { runs: vec![], indent: indent(&lists, quote), prefix: Some(prefix), quote: quote > 0 });
+        }
+        out.push(Block::Code { text, indent: indent(&lists, quote) });
+      }
+      Event::Start(Tag::List(start)) => {
+        flush!();
+        lists.push(start);
+      }
+      Event::End(TagEnd::List(_)) => {
+        flush!();
+        lists.pop();
+      }
+      Event::Start(Tag::Item) => {
+        flush!();
+        prefix = match lists.last_mut() {
+          Some(Some(n)) => {
+            *n += 1;
+            Some(format!("{}.", *n - 1))
+          }
+          _ => Some("•".into()),
+        };
+      }
+      Event::End(TagEnd::Item) => flush!(),
+      Event::TaskListMarker(checked) => runs.push(Run::new(Face::Mono, if checked { "[x] " } else { "[ ] " })),
+      Event::Start(Tag::Strong) => bold += 1,
+      Event::End(TagEnd::Strong) => bold -= 1,
+      Event::Start(Tag::Emphasis) => italic += 1,
+      Event::End(TagEnd::Emphasis) => italic -= 1,
+      Event::Start(Tag::Link { dest_url, .. }) => links.push(dest_url.to_string()),
+      Event::End(TagEnd::Link) => {
+        let url = links.pop().unwrap_or_default();
+        let label: String = runs.iter().map(|r| r.text.as_str()).collect();
+        if !url.is_empty() && !label.ends_with(url.as_str()) {
+          runs.push(Run::new(face, format!(" ({url})")));
+        }
+      }
+      Event::Start(Tag::Table(_)) => {
+        flush!();
+        table = Some(vec![]);
+      }
+      Event::End(TagEnd::Table) => {
+        if let Some(rows) = table.take() {
+          out.push(Block::Table(rows));
+        }
+      }
+      Event::Start(Tag::TableHead | Tag::TableRow) => {
+        if let Some(rows) = table.as_mut() {
+          rows.push(vec![]);
+        }
+      }
+      Event::End(TagEnd::TableCell) => {
+        if let Some(row) = table.as_mut().and_then(|rows| rows.last_mut()) {
+          row.push(std::mem::take(&mut runs));
+        }
+      }
+      Event::Text(text) if code.is_some() => code.get_or_insert_default().push_str(&text),
+      Event::Text(text) | Event::Html(text) | Event::InlineHtml(text) => runs.push(Run::new(face, text.to_string())),
+      Event::Code(text) => runs.push(Run::new(Face::Mono, text.to_string())),
+      Event::InlineMath(text) => runs.push(Run::new(Face::Mono, format!("${text}$"))),
+      Event::DisplayMath(text) => {
+        flush!();
+        out.push(Block::Code { text: format!("$$ {} $$", text.trim()), indent: indent(&lists, quote) });
+      }
+      Event::SoftBreak => runs.push(Run::new(face, " ")),
+      Event::HardBreak => runs.push(Run::new(face, "\n")),
+      Event::Rule => {
+        flush!();
+        out.push(Block::Rule);
+      }
+      _ => {}
+    }
+  }
+  flush!();
+  out
+}
+
+// ---------- Layout ----------
+/// Fonts added to the document so far, by slot.
+struct Fonts {
+  /// Every character the document can draw; fonts are subset to these.
+  chars: BTreeSet<char>,
+  added: RefCell<HashMap<Slot, IndirectFontRef>>,
+  /// First failure to embed a font, reported once the document is done.
+  error: RefCell<Option<printpdf::Error>>,
+}
+
+impl Fonts {
+  fn get(&self, doc: &PdfDocumentReference, slot: Slot) -> Option<IndirectFontRef> {
+    if let Some(font) = self.added.borrow().get(&slot) {
+      return Some(font.clone());
+    }
+    let font = slot.font();
+    let added = match subset(font, &self.chars) {
+      Some(bytes) => doc.add_external_font(bytes.as_slice()),
+      None => doc.add_external_font(font.bytes),
+    };
+    match added {
+      Ok(font) => {
+        self.added.borrow_mut().insert(slot, font.clone());
+        Some(font)
+      }
+      Err(e) => {
+        self.error.borrow_mut().get_or_insert(e);
+        None
+      }
+    }
+  }
+}
+
+fn color((r, g, b): (f32, f32, f32)) -> Color {
+  Color::Rgb(Rgb::new(r, g, b, None))
+}
+
+/// Splits `runs` into lines no wider than `width`; `\n` forces a break and a
+/// word wider than a whole line is cut.
+fn wrap(runs: &[Run], size: f32, width: f32) -> Vec<Vec<Run>> {
+  let mut lines: Vec<Vec<Run>> = vec![];
+  let mut line: Vec<Run> = vec![];
+  let mut line_w = 0.0;
+  let mut space: Option<Face> = None;
+
+  let push = |line: &mut Vec<Run>, face: Face, text: &str| match line.last_mut() {
+    Some(last) if last.face == face => last.text.push_str(text),
+    _ => line.push(Run::new(face, text)),
+  };
+
+  for run in runs {
+    let text: String = run.text.chars().filter_map(|c| if c == '\n' { Some(c) } else { printable(c) }).collect();
+    for (i, part) in text.split('\n').enumerate() {
+      if i > 0 {
+        lines.push(std::mem::take(&mut line));
+        line_w = 0.0;
+        space = None;
+      }
+      for (j, word) in part.split(' ').enumerate() {
+        if j > 0 && !line.is_empty() {
+          space = Some(run.face);
+        }
+        if word.is_empty() {
+          continue;
+        }
+        let space_w = space.map_or(0.0, |f| f.advance(' ', size));
+        let word_w = run.face.width(word, size);
+        if !line.is_empty() && line_w + space_w + word_w > width {
+          lines.push(std::mem::take(&mut line));
+          line_w = 0.0;
+          space = None;
+        } else if let Some(face) = space.take() {
+          push(&mut line, face, " ");
+          line_w += space_w;
+        }
+
+        let mut piece = String::new();
+        for c in word.chars() {
+          let w = run.face.advance(c, size);
+          if line_w + w > width && !(line.is_empty() && piece.is_empty()) {
+            push(&mut line, run.face, &piece);
+            lines.push(std::mem::take(&mut line));
+            piece.clear();
+            line_w = 0.0;
+          }
+          piece.push(c);
+          line_w += w;
+        }
+        push(&mut line, run.face, &piece);
+      }
+    }
+  }
+  if !line.is_empty() {
+    lines.push(line);
+  }
+  lines
+}
+
+struct Writer {
+  doc: PdfDocumentReference,
+  fonts: Fonts,
+  /// Content pages, numbered once the document is complete.
+  pages: Vec<PdfLayerReference>,
+  layer: PdfLayerReference,
+  /// Distance from the top edge of the page, in mm.
+  y: f32,
+}
+
+impl Writer {
+  fn line_height(size: f32) -> f32 {
+    size * LEADING * MM_PER_PT
+  }
+
+  fn new_page(&mut self) {
+    let (page, layer) = self.doc.add_page(Mm(PAGE_W), Mm(PAGE_H), "Layer 1");
+    self.layer = self.doc.get_page(page).get_layer(layer);
+    self.pages.push(self.layer.clone());
+    self.y = MARGIN;
+  }
+
+  /// Starts a new page unless `height` more mm fit on this one.
+  fn ensure(&mut self, height: f32) {
+    if self.y + height > PAGE_H - MARGIN - FOOTER {
+      self.new_page();
+    }
+  }
+
+  /// Draws `runs` on one line, switching fonts wherever the run's own face
+  /// lacks a glyph; characters no font has are drawn as `MISSING`.
+  fn text(&self, runs: &[Run], size: f32, x: f32, rgb: (f32, f32, f32)) {
+    let baseline = PAGE_H - self.y - size * MM_PER_PT * 1.1;
+    self.layer.set_fill_color(color(rgb));
+    let mut x = x;
+    for run in runs {
+      let mut pieces: Vec<(Slot, String)> = vec![];
+      for c in run.text.chars() {
+        let (slot, c) = match slot(run.face, c) {
+          Some(slot) => (slot, c),
+          None => (Slot::Face(Face::Regular), MISSING),
+        };
+        match pieces.last_mut() {
+          Some((last, text)) if *last == slot => text.push(c),
+          _ => pieces.push((slot, c.to_string())),
+        }
+      }
+      for (slot, text) in pieces {
+        if let Some(font) = self.fonts.get(&self.doc, slot) {
+          self.layer.use_text(text.as_str(), size, Mm(x), Mm(baseline), &font);
+        }
+        x += text.chars().map(|c| slot.font().advance(c, size)).sum::<f32>();
+      }
+    }
+  }
+
+  fn rule(&self, x1: f32, x2: f32, rgb: (f32, f32, f32), thickness: f32) {
+    let y = Mm(PAGE_H - self.y);
+    self.layer.set_outline_color(color(rgb));
+    self.layer.set_outline_thickness(thickness);
+    self.layer.add_line(Line {
+      points: vec![(Point::new(Mm(x1), y), false), (Point::new(Mm(x2), y), false)],
+      is_closed: false,
+    });
+  }
+
+  fn paragraph(&mut self, runs: &[Run], size: f32, indent: f32, prefix: Option<&str>, rgb: (f32, f32, f32)) {
+    let x = MARGIN + indent;
+    let lh = Self::line_height(size);
+    let lines = wrap(runs, size, PAGE_W - MARGIN - x);
+    if lines.is_empty() {
+      if let Some(prefix) = prefix {
+        self.ensure(lh);
+        self.text(&[Run::new(Face::Regular, prefix)], size, x - LIST_INDENT * 0.8, rgb);
+        self.y += lh;
+      }
+      return;
+    }
+    for (i, line) in lines.iter().enumerate() {
+      self.ensure(lh);
+      if let Some(prefix) = prefix.filter(|_| i == 0) {
+        self.text(&[Run::new(Face::Regular, prefix)], size, x - LIST_INDENT * 0.8, rgb);
+      }
+      self.text(line, size, x, rgb);
+      self.y += lh;
+    }
+  }
+
+  fn code(&mut self, code: &str, indent: f32) {
+    let x = MARGIN + indent;
+    let lh = Self::line_height(CODE_SIZE);
+    let pad = 2.0;
+    let width = PAGE_W - MARGIN - x - 2.0 * pad;
+    let mut lines = vec![];
+    for line in code.trim_end_matches('\n').split('\n') {
+      // cut by width: glyphs from a CJK fallback are wider than the mono cell
+      let (mut piece, mut piece_w) = (String::new(), 0.0);
+      for c in line.chars().filter_map(printable) {
+        let w = Face::Mono.advance(c, CODE_SIZE);
+        if piece_w + w > width && !piece.is_empty() {
+          lines.push(std::mem::take(&mut piece));
+          piece_w = 0.0;
+        }
+        piece.push(c);
+        piece_w += w;
+      }
+      lines.push(piece);
+    }
+
+    self.ensure(lh + pad);
+    self.y += pad / 2.0;
+    for line in lines {
+      self.ensure(lh);
+      let top = PAGE_H - self.y;
+      self.layer.set_fill_color(color(CODE_BG));
+      self.layer.add_rect(Rect::new(Mm(x), Mm(top - lh), Mm(PAGE_W - MARGIN), Mm(top)).with_mode(PaintMode::Fill));
+      self.text(&[Run::new(Face::Mono, line)], CODE_SIZE, x + pad, TEXT);
+      self.y += lh;
+    }
+    self.y += pad / 2.0;
+  }
+
+  fn table(&mut self, rows: &[Vec<Vec<Run>>]) {
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    if columns == 0 {
+      return;
+    }
+    let size = BODY_SIZE - 1.0;
+    let lh = Self::line_height(size);
+    let col_w = (PAGE_W - 2.0 * MARGIN) / columns as f32;
+    let pad = 1.5;
+    for (i, row) in rows.iter().enumerate() {
+      let cells: Vec<Vec<Vec<Run>>> = row
+        .iter()
+        .map(|cell| {
+          let cell: Vec<Run> = cell
+            .iter()
+            .map(|r| Run::new(if i == 0 && r.face == Face::Regular { Face::Bold } else { r.face }, r.text.clone()))
+            .collect();
+          wrap(&cell, size, col_w - 2.0 * pad)
+        })
+        .collect();
+      let height = cells.iter().map(Vec::len).max().unwrap_or(1).max(1) as f32 * lh + pad;
+      self.ensure(height);
+      let top = self.y;
+      for (c, lines) in cells.iter().enumerate() {
+        self.y = top + pad / 2.0;
+        for line in lines {
+          self.text(line, size, MARGIN + c as f32 * col_w + pad, TEXT);
+          self.y += lh;
+        }
+      }
+      self.y = top + height;
+      self.rule(MARGIN, PAGE_W - MARGIN, RULE, if i == 0 { 0.8 } else { 0.4 });
+    }
+    self.y += 1.0;
+  }
+
+  fn block(&mut self, block: &Block) {
+    match block {
+      Block::Heading(level, runs) => {
+        let size = match level {
+          1 => 17.0,
+          2 => 15.0,
+          3 => 13.0,
+          _ => 11.5,
+        };
+        let runs: Vec<Run> = runs.iter().map(|r| Run::new(if r.face == Face::Mono { Face::Mono } else { Face::Bold }, r.text.clone())).collect();
+        self.y += 2.0;
+        self.ensure(Self::line_height(size) * 2.0);
+        self.paragraph(&runs, size, 0.0, None, TEXT);
+      }
+      Block::Para { runs, indent, prefix, quote } => {
+        self.paragraph(runs, BODY_SIZE, *indent, prefix.as_deref(), if *quote { MUTED } else { TEXT });
+      }
+      Block::Code { text, indent } => self.code(text, *indent),
+      Block::Table(rows) => self.table(rows),
+      Block::Rule => {
+        self.ensure(4.0);
+        self.y += 2.0;
+        self.rule(MARGIN, PAGE_W - MARGIN, RULE, 0.6);
+        self.y += 2.0;
+      }
+    }
+    self.y += 1.5;
+  }
+
+  fn title_page(&mut self, conversation: &Conversation, exported_ms: u64, missing: &BTreeSet<char>) {
+    self.y = PAGE_H * 0.3;
+    let title = [Run::new(Face::Bold, conversation.title.trim())];
+    self.paragraph(&title, 24.0, 0.0, None, TEXT);
+    self.y += 4.0;
+    self.rule(MARGIN, PAGE_W - MARGIN, RULE, 1.0);
+    self.y += 4.0;
+    let meta = [
+      conversation.url.clone(),
+      format!("Exported {}", export::rfc3339(exported_ms)),
+      format!("{} messages", conversation.messages.len()),
+    ];
+    for line in meta.iter().filter(|l| !l.is_empty()) {
+      self.paragraph(&[Run::new(Face::Regular, line.as_str())], BODY_SIZE, 0.0, None, MUTED);
+    }
+    if !missing.is_empty() {
+      let codes: Vec<String> = missing.iter().take(12).map(|c| format!("U+{:04X}", *c as u32)).collect();
+      let more = if missing.len() > codes.len() { ", ..." } else { "" };
+      let notice = format!(
+        "{} characters have no font on this system and are shown as {MISSING}: {}{more}",
+        missing.len(),
+        codes.join(", ")
+      );
+      self.y += 4.0;
+      self.paragraph(&[Run::new(Face::Regular, notice)], BODY_SIZE, 0.0, None, NOTICE);
+    }
+  }
+
+  fn page_numbers(&self) {
+    let total = self.pages.len();
+    for (i, layer) in self.pages.iter().enumerate() {
+      let label = format!("{} / {total}", i + 1);
+      let x = (PAGE_W - Face::Regular.width(&label, 9.0)) / 2.0;
+      let Some(font) = self.fonts.get(&self.doc, Slot::Face(Face::Regular)) else { return };
+      layer.set_fill_color(color(MUTED));
+      layer.use_text(label, 9.0, Mm(x), Mm(MARGIN / 2.0), &font);
+    }
+  }
+}
+
+/// A4 pages of real text: a title page, then every message under its role,
+/// numbered at the bottom. Also returns the characters no font could draw.
+pub fn to_pdf(conversation: &Conversation, exported_ms: u64) -> Result<(Vec<u8>, BTreeSet<char>), printpdf::Error> {
+  let markdown: Vec<String> = conversation.messages.iter().map(export::message_markdown).collect();
+  let texts = || markdown.iter().chain([&conversation.title, &conversation.url]);
+  let mut missing = BTreeSet::new();
+  for text in texts() {
+    missing_chars(text, &mut missing);
+  }
+  // labels, list bullets and page numbers are drawn in addition to the text
+  let mut chars: BTreeSet<char> = (' '..='~').chain(['•', MISSING]).collect();
+  chars.extend(texts().flat_map(|t| t.chars()));
+
+  let (doc, page, layer) = PdfDocument::new(conversation.title.trim(), Mm(PAGE_W), Mm(PAGE_H), "Layer 1");
+  let fonts = Fonts { chars, added: RefCell::default(), error: RefCell::default() };
+  let layer = doc.get_page(page).get_layer(layer);
+  let mut w = Writer { doc, fonts, pages: vec![], layer, y: MARGIN };
+  w.title_page(conversation, exported_ms, &missing);
+
+  w.new_page();
+  for (i, (message, markdown)) in conversation.messages.iter().zip(&markdown).enumerate() {
+    if i > 0 {
+      w.y += 4.0;
+    }
+    let size = 12.5;
+    w.ensure(Writer::line_height(size) * 3.0);
+    w.paragraph(&[Run::new(Face::Bold, export::role_title(&message.role))], size, 0.0, None, TEXT);
+    w.rule(MARGIN, PAGE_W - MARGIN, RULE, 0.8);
+    w.y += 2.5;
+    for block in blocks(markdown) {
+      w.block(&block);
+    }
+  }
+  w.page_numbers();
+  if let Some(e) = w.fonts.error.take() {
+    return Err(e);
+  }
+  Ok((w.doc.save_to_bytes()?, missing))
+}
+
+#[derive(Debug, Serialize)]
+pub struct PdfExport {
+  pub entry: Option<FileEntry>,
+  /// Characters no bundled or system font covers, drawn as `MISSING`.
+  pub missing: String,
+}
+
+// ---------- Tauri commands ----------
+/// Renders `conversation` to `download/pdf/<id>.pdf`, listed in
+/// `chat.download.json` under its title.
+#[tauri::command]
+pub fn export_pdf(id: String, conversation: Conversation) -> Result<PdfExport, String> {
+  let (pdf, missing) = to_pdf(&conversation, now_ms()).map_err(|e| e.to_string())?;
+  let entry = storage::store(&chat_root(), &format!("download/pdf/{id}.pdf"), &pdf, Some(&conversation.title)).map_err(|e| e.to_string())?;
+  Ok(PdfExport { entry, missing: missing.into_iter().collect() })
+}
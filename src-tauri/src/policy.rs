@@ -0,0 +1,105 @@
+// src-tauri/src/policy.rs  — admin-managed /etc/chatgpt/policy.json that pins config fields
+
+use std::{fs, path::Path};
+
+use serde_json::{Map, Value};
+
+use crate::conf::{ChatConfJson, ConfError, FieldError};
+
+/// Every key in this file is locked to its value, e.g.
+/// `{ "origin": "https://chat.example.com", "auto_update": false }`.
+pub const POLICY_PATH: &str = "/etc/chatgpt/policy.json";
+
+/// Locked fields and their values. A missing file means no policy; an
+/// unreadable file or invalid values are reported and skipped.
+pub fn load() -> Map<String, Value> {
+  let path = Path::new(POLICY_PATH);
+  if !path.exists() {
+    return Map::new();
+  }
+
+  let parsed = fs::read_to_string(path)
+    .map_err(|e| e.to_string())
+    .and_then(|raw| serde_json::from_str::<Value>(&raw).map_err(|e| e.to_string()));
+  let fields = match parsed {
+    Ok(Value::Object(fields)) => fields,
+    Ok(_) => {
+      eprintln!("Ignoring {POLICY_PATH}: must be a JSON object");
+      return Map::new();
+    }
+    Err(e) => {
+      eprintln!("Ignoring {POLICY_PATH}: {e}");
+      return Map::new();
+    }
+  };
+
+  let defaults = serde_json::to_value(ChatConfJson::defaults()).unwrap_or_default();
+  fields
+    .into_iter()
+    .filter_map(|(field, v)| {
+      if field == "schema_version" || defaults.get(&field).is_none() {
+        eprintln!("Ignoring {POLICY_PATH} entry {field}: not a config field");
+        return None;
+      }
+      // keep the normalized form (e.g. lowercase theme) so comparisons are exact
+      match normalized(&field, v) {
+        Ok(v) => Some((field, v)),
+        Err(e) => {
+          eprintln!("Ignoring {POLICY_PATH} entry {field}: {e}");
+          None
+        }
+      }
+    })
+    .collect()
+}
+
+/// `value` the way `validate` stores it in `field`, e.g. an origin with its trailing `/`.
+fn normalized(field: &str, value: Value) -> Result<Value, ConfError> {
+  let probe = Map::from_iter([(field.to_string(), value)]);
+  let conf = serde_json::to_value(ChatConfJson::validate(Value::Object(probe))?)?;
+  Ok(conf[field].clone())
+}
+
+/// Fields of `patch` that try to move a locked field away from its pinned
+/// value. Values are normalized first, so restating the pinned value passes.
+pub fn violations(policy: &Map<String, Value>, patch: &Value) -> Vec<FieldError> {
+  let Value::Object(patch) = patch else { return vec![] };
+  patch
+    .iter()
+    .filter(|(field, v)| {
+      policy
+        .get(*field)
+        .is_some_and(|locked| !normalized(field, (*v).clone()).is_ok_and(|v| v == *locked))
+    })
+    .map(|(field, _)| FieldError { field: field.clone(), message: format!("locked by {POLICY_PATH}") })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  fn policy(fields: Value) -> Map<String, Value> {
+    let Value::Object(fields) = fields else { unreachable!() };
+    fields.into_iter().map(|(field, v)| (field.clone(), normalized(&field, v).unwrap())).collect()
+  }
+
+  #[test]
+  fn restating_a_locked_value_passes() {
+    let policy = policy(json!({ "origin": "https://chat.example.com", "theme": "dark" }));
+    let patch = json!({ "origin": "https://chat.example.com", "theme": "dark", "tray": false });
+    assert!(violations(&policy, &patch).is_empty());
+  }
+
+  #[test]
+  fn moving_a_locked_value_is_refused() {
+    let policy = policy(json!({ "origin": "https://chat.example.com" }));
+    let fields: Vec<_> = violations(&policy, &json!({ "origin": "https://other.example.com" }))
+      .into_iter()
+      .map(|e| e.field)
+      .collect();
+    assert_eq!(fields, ["origin"]);
+    assert_eq!(violations(&policy, &json!({ "origin": 42 })).len(), 1);
+  }
+}
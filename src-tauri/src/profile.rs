@@ -0,0 +1,145 @@
+// src-tauri/src/profile.rs  — named profiles, one process (and data dir) per profile
+
+use std::{
+  fs,
+  path::PathBuf,
+  process::Command,
+  sync::OnceLock,
+};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::menu::build_menu;
+use crate::utils::{chat_root, data_home};
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+static ACTIVE: OnceLock<String> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileInfo {
+  pub name: String,
+  pub active: bool,
+  pub root: PathBuf,
+}
+
+// ---------- Active profile (picked once, from `--profile <name>`) ----------
+pub fn init<I: IntoIterator<Item = String>>(args: I) {
+  let mut args = args.into_iter();
+  let mut name = None;
+  while let Some(arg) = args.next() {
+    if arg == "--profile" {
+      name = args.next();
+    } else if let Some(v) = arg.strip_prefix("--profile=") {
+      name = Some(v.to_string());
+    }
+  }
+
+  let name = match name {
+    Some(n) if is_valid_name(&n) => n,
+    Some(n) => {
+      eprintln!("Ignoring invalid profile name \"{n}\", using \"{DEFAULT_PROFILE}\"");
+      DEFAULT_PROFILE.to_string()
+    }
+    None => DEFAULT_PROFILE.to_string(),
+  };
+  let _ = ACTIVE.set(name);
+}
+
+pub fn active() -> &'static str {
+  ACTIVE.get().map(String::as_str).unwrap_or(DEFAULT_PROFILE)
+}
+
+pub fn is_default() -> bool {
+  active() == DEFAULT_PROFILE
+}
+
+fn is_valid_name(name: &str) -> bool {
+  !name.is_empty()
+    && name.len() <= 32
+    && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+// ---------- Paths ----------
+/// Every profile, the default one included, lives in `<base>/profiles/<name>`,
+/// so no profile's dir contains another's.
+pub fn profile_dir(base: PathBuf, name: &str) -> PathBuf {
+  base.join("profiles").join(name)
+}
+
+/// WebKit data store of the active profile; `None` keeps the shared default
+/// store for the default profile so it stays logged in.
+pub fn webview_data_dir() -> Option<PathBuf> {
+  (!is_default()).then(|| chat_root().join("webview"))
+}
+
+/// D-Bus name for the single-instance plugin, so each profile can run next to the others.
+pub fn dbus_id(identifier: &str) -> String {
+  if is_default() {
+    identifier.to_string()
+  } else {
+    format!("{identifier}.profile_{}", active())
+  }
+}
+
+pub fn list() -> Vec<String> {
+  let mut names = vec![DEFAULT_PROFILE.to_string()];
+  if let Ok(entries) = fs::read_dir(data_home().join("profiles")) {
+    let mut others: Vec<String> = entries
+      .flatten()
+      .filter(|e| e.path().is_dir())
+      .filter_map(|e| e.file_name().into_string().ok())
+      .filter(|n| is_valid_name(n) && n != DEFAULT_PROFILE)
+      .collect();
+    others.sort();
+    names.extend(others);
+  }
+  names
+}
+
+/// Starts (or, through the single-instance plugin, focuses) the app for `name`.
+pub fn open(name: &str) -> std::io::Result<()> {
+  let exe = std::env::current_exe()?;
+  Command::new(exe).arg("--profile").arg(name).spawn()?;
+  Ok(())
+}
+
+// ---------- Tauri commands ----------
+#[tauri::command]
+pub fn list_profiles() -> Vec<ProfileInfo> {
+  list()
+    .into_iter()
+    .map(|name| ProfileInfo { active: name == active(), root: profile_dir(data_home(), &name), name })
+    .collect()
+}
+
+#[tauri::command]
+pub fn create_profile(app: AppHandle, name: String) -> Result<ProfileInfo, String> {
+  if !is_valid_name(&name) {
+    return Err("Profile names may only contain letters, digits, \"-\" and \"_\" (max 32)".into());
+  }
+  let root = profile_dir(data_home(), &name);
+  fs::create_dir_all(&root).map_err(|e| e.to_string())?;
+
+  // refresh the profile switcher
+  if let Ok(menu) = build_menu(&app) {
+    let _ = app.set_menu(menu);
+  }
+  Ok(ProfileInfo { active: name == active(), root, name })
+}
+
+#[tauri::command]
+pub fn open_profile(app: AppHandle, name: String) -> Result<(), String> {
+  if name == active() {
+    if let Some(core) = app.get_webview_window("core") {
+      let _ = core.show();
+      let _ = core.set_focus();
+    }
+    return Ok(());
+  }
+  if !list().contains(&name) {
+    return Err(format!("No profile named \"{name}\""));
+  }
+  open(&name).map_err(|e| e.to_string())
+}
@@ -0,0 +1,218 @@
+// src-tauri/src/prompts.rs  — awesome-chatgpt-prompts sync and the prompt records the model views share
+
+use std::{collections::HashMap, fs, path::Path, sync::Mutex};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::conf::{now_ms, ChatConfJson};
+use crate::history;
+use crate::parser::{self, ParseWarning, ParsedPrompts};
+use crate::utils::{chat_root, create_file};
+
+pub const GITHUB_PROMPTS_CSV_URL: &str = "https://raw.githubusercontent.com/f/awesome-chatgpt-prompts/main/prompts.csv";
+pub const CHAT_PROMPTS_CSV: &str = "chat.prompts.csv";
+pub const CHAT_MODEL_JSON: &str = "chat.model.json";
+/// Per-source record lists read by the SyncPrompts / SyncRecord views.
+pub const CACHE_MODEL_DIR: &str = "cache_model";
+pub const SYNC_PROMPTS_ID: &str = "chatgpt_prompts";
+
+/// Serializes read-modify-write cycles on `chat.model.json`, which the
+/// scheduler, the `dir` watcher and the sync commands all update.
+static MODEL_WRITE: Mutex<()> = Mutex::new(());
+
+/// One slash command as stored in `cache_model/*.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptRecord {
+  pub cmd: String,
+  pub act: String,
+  pub prompt: String,
+  #[serde(default)]
+  pub tags: Vec<String>,
+  #[serde(default = "enabled")]
+  pub enable: bool,
+}
+
+fn enabled() -> bool {
+  true
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncReport {
+  pub added: usize,
+  pub changed: usize,
+  pub removed: usize,
+  pub last_updated: u64,
+  pub data: Vec<PromptRecord>,
+  pub warnings: Vec<ParseWarning>,
+}
+
+/// Same slug as `genCmd` in the frontend: `Linux Terminal` -> `linux_terminal`.
+pub fn gen_cmd(act: &str) -> String {
+  let separators = Regex::new(r"\s+|/+").unwrap();
+  separators
+    .replace_all(act, "_")
+    .chars()
+    .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
+    .collect::<String>()
+    .to_lowercase()
+}
+
+pub fn write_csv(records: &[PromptRecord]) -> Result<Vec<u8>, csv::Error> {
+  let mut wtr = csv::Writer::from_writer(vec![]);
+  wtr.write_record(["act", "prompt"])?;
+  for r in records {
+    wtr.write_record([&r.act, &r.prompt])?;
+  }
+  wtr.into_inner().map_err(|e| e.into_error().into())
+}
+
+// ---------- Files under chat_root() ----------
+pub fn read_cache(root: &Path, id: &str) -> Vec<PromptRecord> {
+  fs::read(root.join(CACHE_MODEL_DIR).join(format!("{id}.json")))
+    .ok()
+    .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+    .unwrap_or_default()
+}
+
+pub fn write_cache(root: &Path, id: &str, records: &[PromptRecord]) -> anyhow::Result<()> {
+  let path = root.join(CACHE_MODEL_DIR).join(format!("{id}.json"));
+  create_file(path, serde_json::to_vec_pretty(records)?)?;
+  Ok(())
+}
+
+/// Reads `chat.model.json`, lets `f` change it and writes it back under
+/// `MODEL_WRITE`, creating the file like the frontend's `readJSON` does.
+pub fn update_model(root: &Path, f: impl FnOnce(&mut Value) -> anyhow::Result<()>) -> anyhow::Result<()> {
+  let _guard = MODEL_WRITE.lock().unwrap_or_else(|e| e.into_inner());
+  let path = root.join(CHAT_MODEL_JSON);
+  let mut model = fs::read(&path)
+    .ok()
+    .and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok())
+    .filter(Value::is_object)
+    .unwrap_or_else(|| json!({ "name": "ChatGPT Model", "link": "https://github.com/SpellboundScents/ChatGPT" }));
+  f(&mut model)?;
+  create_file(path, serde_json::to_vec_pretty(&model)?)?;
+  Ok(())
+}
+
+/// Sets `chat.model.json[key] = value`.
+pub fn set_model_entry(root: &Path, key: &str, value: Value) -> anyhow::Result<()> {
+  update_model(root, |model| {
+    model[key] = value;
+    Ok(())
+  })
+}
+
+// ---------- Sync ----------
+pub async fn fetch(url: &str) -> reqwest::Result<Vec<u8>> {
+  let res = reqwest::get(url).await?.error_for_status()?;
+  Ok(res.bytes().await?.to_vec())
+}
+
+/// Fetches `url` and rewrites the prompt files under `root`; the command
+/// passes the configured URL and `chat_root()`.
+pub async fn sync_from(url: &str, root: &Path) -> anyhow::Result<SyncReport> {
+  let bytes = fetch(url).await?;
+  apply(url, &bytes, root)
+}
+
+/// Rewrites the prompt files under `root` from the CSV fetched from `url`.
+/// Enable flags of prompts that survive the sync are kept.
+pub fn apply(url: &str, bytes: &[u8], root: &Path) -> anyhow::Result<SyncReport> {
+  let ParsedPrompts { records: mut data, warnings, .. } = parser::parse_csv(bytes, SYNC_PROMPTS_ID);
+  if data.is_empty() {
+    anyhow::bail!("{url} did not contain any act/prompt rows");
+  }
+
+  let before = read_cache(root, SYNC_PROMPTS_ID);
+  let previous: HashMap<&str, &PromptRecord> = before.iter().map(|r| (r.act.as_str(), r)).collect();
+  let (mut added, mut changed) = (0, 0);
+  for record in &mut data {
+    match previous.get(record.act.as_str()) {
+      None => added += 1,
+      Some(old) => {
+        if old.prompt != record.prompt {
+          changed += 1;
+        }
+        record.enable = old.enable;
+      }
+    }
+  }
+  let removed = previous.keys().filter(|act| !data.iter().any(|r| r.act == **act)).count();
+
+  let last_updated = now_ms();
+  write_cache(root, SYNC_PROMPTS_ID, &data)?;
+  history::record(root, SYNC_PROMPTS_ID, "awesome-chatgpt-prompts", &before, &data, None)?;
+  create_file(root.join(CHAT_PROMPTS_CSV), write_csv(&data)?)?;
+  set_model_entry(root, "sync_prompts", json!({ "id": SYNC_PROMPTS_ID, "last_updated": last_updated }))?;
+
+  Ok(SyncReport { added, changed, removed, last_updated, data, warnings })
+}
+
+// ---------- Tauri commands ----------
+#[tauri::command]
+pub async fn sync_prompts() -> Result<SyncReport, String> {
+  let url = ChatConfJson::load().prompts_url.url().to_string();
+  sync_from(&url, &chat_root()).await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::io::{Read, Write};
+  use std::net::TcpListener;
+  use std::path::PathBuf;
+
+  use super::*;
+
+  /// Serves `body` once from a loopback port and returns its URL.
+  fn serve(body: &'static str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/prompts.csv", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+      let (mut stream, _) = listener.accept().unwrap();
+      let mut request = vec![];
+      let mut buf = [0; 1024];
+      while !request.ends_with(b"\r\n\r\n") {
+        let n = stream.read(&mut buf).unwrap();
+        if n == 0 {
+          break;
+        }
+        request.extend_from_slice(&buf[..n]);
+      }
+      let head = format!("HTTP/1.1 200 OK\r\nContent-Type: text/csv\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len());
+      stream.write_all(head.as_bytes()).unwrap();
+      stream.write_all(body.as_bytes()).unwrap();
+    });
+    url
+  }
+
+  fn temp_root() -> PathBuf {
+    let root = std::env::temp_dir().join(format!("chatgpt-sync-{}-{}", std::process::id(), now_ms()));
+    fs::create_dir_all(&root).unwrap();
+    root
+  }
+
+  #[test]
+  fn sync_from_local_stand_in() {
+    let root = temp_root();
+    let sync = |body| tauri::async_runtime::block_on(sync_from(&serve(body), &root)).unwrap();
+
+    let first = sync("act,prompt\nLinux Terminal,act as a terminal\nTranslator,translate\n");
+    assert_eq!((first.added, first.changed, first.removed), (2, 0, 0));
+
+    let second = sync("act,prompt\nLinux Terminal,act as a bash terminal\nPoet,write a poem\n");
+    assert_eq!((second.added, second.changed, second.removed), (1, 1, 1));
+
+    let csv = fs::read_to_string(root.join(CHAT_PROMPTS_CSV)).unwrap();
+    assert_eq!(csv, "act,prompt\nLinux Terminal,act as a bash terminal\nPoet,write a poem\n");
+    let model: Value = serde_json::from_slice(&fs::read(root.join(CHAT_MODEL_JSON)).unwrap()).unwrap();
+    assert_eq!(model["sync_prompts"]["id"], SYNC_PROMPTS_ID);
+    assert_eq!(model["sync_prompts"]["last_updated"], second.last_updated);
+    let cmds: Vec<String> = read_cache(&root, SYNC_PROMPTS_ID).into_iter().map(|r| r.cmd).collect();
+    assert_eq!(cmds, ["linux_terminal", "poet"]);
+
+    let _ = fs::remove_dir_all(&root);
+  }
+}
@@ -0,0 +1,219 @@
+// src-tauri/src/scheduler.rs  — background re-sync of the remote prompt sources every `sync_interval` minutes
+
+use std::{
+  collections::HashMap,
+  fs,
+  path::PathBuf,
+  sync::atomic::{AtomicBool, Ordering},
+  time::Duration,
+};
+
+use reqwest::{header, StatusCode};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Notify;
+
+use crate::cmd_index;
+use crate::conf::{fnv1a, ChatConfJson};
+use crate::prompts::{self, PromptRecord};
+use crate::sources::{self, Pins, SourceError};
+use crate::utils::{chat_cache_dir, chat_root, create_file};
+
+/// Longest wait after repeated failures, whatever the interval.
+const MAX_BACKOFF: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Reported by the webviews (`navigator.onLine`); runs are held while offline.
+static ONLINE: AtomicBool = AtomicBool::new(true);
+/// Restarts the wait after the interval changed or the network came back.
+static WAKE: Notify = Notify::const_new();
+
+// ---------- HTTP validators (per URL) ----------
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Validators {
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  etag: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  last_modified: Option<String>,
+  /// Body hash, for servers that send neither header.
+  #[serde(default)]
+  hash: String,
+  /// The pins the body was verified against; under other pins it is fetched again.
+  #[serde(default)]
+  pins: Pins,
+}
+
+fn validators_path() -> PathBuf {
+  chat_cache_dir().join("http.json")
+}
+
+fn read_validators() -> HashMap<String, Validators> {
+  fs::read(validators_path())
+    .ok()
+    .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+    .unwrap_or_default()
+}
+
+/// `None` when the server answered 304 or sent the body we already have,
+/// both only as long as that body was verified against `pins`.
+async fn fetch_if_changed(
+  client: &reqwest::Client,
+  url: &str,
+  pins: &Pins,
+  validators: &mut HashMap<String, Validators>,
+) -> reqwest::Result<Option<Vec<u8>>> {
+  let known = validators.get(url).filter(|v| v.pins == *pins).cloned().unwrap_or_default();
+  let mut req = client.get(url);
+  if let Some(etag) = &known.etag {
+    req = req.header(header::IF_NONE_MATCH, etag);
+  }
+  if let Some(date) = &known.last_modified {
+    req = req.header(header::IF_MODIFIED_SINCE, date);
+  }
+
+  let res = req.send().await?;
+  if res.status() == StatusCode::NOT_MODIFIED {
+    return Ok(None);
+  }
+  let res = res.error_for_status()?;
+  let header = |name| res.headers().get(name).and_then(|v| v.to_str().ok()).map(String::from);
+  let (etag, last_modified) = (header(header::ETAG), header(header::LAST_MODIFIED));
+  let bytes = res.bytes().await?.to_vec();
+
+  let hash = fnv1a(&bytes);
+  let unchanged = hash == known.hash;
+  validators.insert(url.to_string(), Validators { etag, last_modified, hash, pins: pins.clone() });
+  Ok((!unchanged).then_some(bytes))
+}
+
+// ---------- One run ----------
+struct Run {
+  /// Display names of the sources whose prompts changed.
+  changed: Vec<String>,
+  failed: usize,
+}
+
+fn same_prompts(a: &[PromptRecord], b: &[PromptRecord]) -> bool {
+  a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.act == b.act && a.prompt == b.prompt)
+}
+
+async fn run() -> Run {
+  let root = chat_root();
+  let client = reqwest::Client::new();
+  let mut validators = read_validators();
+  let mut run = Run { changed: vec![], failed: 0 };
+
+  let url = ChatConfJson::load().prompts_url.url().to_string();
+  match fetch_if_changed(&client, &url, &Pins::default(), &mut validators).await {
+    Ok(None) => {}
+    Ok(Some(bytes)) => match prompts::apply(&url, &bytes, &root) {
+      Ok(report) if report.added + report.changed + report.removed > 0 => {
+        run.changed.push("awesome-chatgpt-prompts".into());
+      }
+      Ok(_) => {}
+      Err(e) => {
+        eprintln!("Background sync of {url} failed: {e}");
+        validators.remove(&url); // fetch it again next time
+        run.failed += 1;
+      }
+    },
+    Err(e) => {
+      eprintln!("Background sync of {url} failed: {e}");
+      run.failed += 1;
+    }
+  }
+
+  let mut statuses = vec![];
+  for source in sources::list() {
+    let Some(url) = source.remote_url() else { continue };
+    let before = prompts::read_cache(&root, &source.id);
+    let loaded = match fetch_if_changed(&client, &url, &source.pins(), &mut validators).await {
+      Ok(None) => continue,
+      Ok(Some(bytes)) => sources::verified(&source, &bytes).await,
+      Err(e) => Err(SourceError::Load(e.to_string())),
+    };
+    let status = sources::apply(&source, loaded);
+    if !status.ok || status.from_cache {
+      validators.remove(&url);
+      run.failed += 1;
+    }
+    // a source emptied after failing its pin changed too
+    if !same_prompts(&before, &prompts::read_cache(&root, &source.id)) {
+      run.changed.push(if source.name.is_empty() { source.id.clone() } else { source.name.clone() });
+    }
+    statuses.push(status);
+  }
+  if !statuses.is_empty()
+    && let Err(e) = sources::record_statuses(&statuses)
+  {
+    eprintln!("Failed to record prompt source statuses: {e}");
+  }
+
+  match serde_json::to_vec_pretty(&validators) {
+    Ok(bytes) => {
+      if let Err(e) = create_file(validators_path(), bytes) {
+        eprintln!("Failed to save {}: {e}", validators_path().display());
+      }
+    }
+    Err(e) => eprintln!("Failed to save {}: {e}", validators_path().display()),
+  }
+  run
+}
+
+// ---------- Loop ----------
+/// The interval doubles with every failed run in a row, up to `MAX_BACKOFF`.
+fn delay(minutes: u64, failures: u32) -> Duration {
+  let base = Duration::from_secs(minutes * 60);
+  base.saturating_mul(1 << failures.min(16)).min(MAX_BACKOFF.max(base))
+}
+
+/// Spawns the scheduler on the Tauri runtime. It idles while `sync_interval`
+/// is 0 and holds a due run until the webviews report being online again.
+pub fn init(app: &AppHandle) {
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    let (mut failures, mut due) = (0u32, false);
+    loop {
+      let minutes = ChatConfJson::load().sync_interval;
+      if minutes == 0 {
+        (failures, due) = (0, false);
+        WAKE.notified().await;
+        continue;
+      }
+      if !due {
+        tokio::select! {
+          _ = tokio::time::sleep(delay(minutes, failures)) => due = true,
+          _ = WAKE.notified() => continue,
+        }
+      }
+      if !ONLINE.load(Ordering::Relaxed) {
+        WAKE.notified().await;
+        continue;
+      }
+
+      due = false;
+      let run = run().await;
+      failures = if run.failed > 0 { failures.saturating_add(1) } else { 0 };
+      if run.changed.is_empty() {
+        continue;
+      }
+      if let Err(e) = cmd_index::rebuild() {
+        eprintln!("Failed to rebuild chat.model.cmd.json: {e}");
+      }
+      let _ = app.emit("notice", format!("Prompts updated: {}", run.changed.join(", ")));
+    }
+  });
+}
+
+/// Called when `sync_interval` changed so the new interval applies right away.
+pub fn reschedule() {
+  WAKE.notify_one();
+}
+
+// ---------- Tauri commands ----------
+#[tauri::command]
+pub fn set_online(online: bool) {
+  let was = ONLINE.swap(online, Ordering::Relaxed);
+  if online && !was {
+    WAKE.notify_one();
+  }
+}
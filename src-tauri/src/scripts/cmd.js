@@ -42,6 +42,10 @@ function init() {
     user-select: none;
     cursor: pointer;
   }
+  .chat-model-cmd-list .cmd-item mark {
+    background: rgba(250,204,21,.45);
+    color: inherit;
+  }
   .chat-model-cmd-list .cmd-item:last-child {
     border-bottom: none;
   }
@@ -67,6 +71,61 @@ function init() {
     text-align: right;
     color: #888;
   }
+  .chat-model-cmd-form {
+    position: absolute;
+    bottom: 60px;
+    left: 0;
+    right: 0;
+    z-index: 9999;
+  }
+  .chat-model-cmd-form>div {
+    font-size: 12px;
+    padding: 8px;
+    border: solid 2px rgba(80,80,80,.3);
+    border-radius: 5px;
+    background-color: #fff;
+    color: #2a2a2a;
+  }
+  .chat-model-cmd-form label {
+    display: flex;
+    align-items: center;
+    gap: 8px;
+    margin-top: 6px;
+  }
+  .chat-model-cmd-form label span {
+    width: 100px;
+    flex-shrink: 0;
+  }
+  .chat-model-cmd-form input, .chat-model-cmd-form select, .chat-model-cmd-form textarea {
+    flex: 1;
+    padding: 2px 4px;
+    border: solid 1px rgba(80,80,80,.3);
+    border-radius: 4px;
+    background: transparent;
+    color: inherit;
+  }
+  .chat-model-cmd-form .invalid input, .chat-model-cmd-form .invalid select, .chat-model-cmd-form .invalid textarea {
+    border-color: #e5484d;
+  }
+  .chat-model-cmd-form .cmd-form-error {
+    margin-top: 6px;
+    color: #e5484d;
+  }
+  .chat-model-cmd-form .cmd-form-actions {
+    display: flex;
+    justify-content: flex-end;
+    gap: 8px;
+    margin-top: 6px;
+  }
+  .chat-model-cmd-form button {
+    padding: 2px 10px;
+    border-radius: 4px;
+    border: solid 1px rgba(80,80,80,.3);
+  }
+  html.dark .chat-model-cmd-form>div {
+    background-color: #4a4a4a;
+    color: #e8e8e8;
+  }
   .chatappico {
     width: 20px;
     height: 20px;
@@ -104,7 +163,9 @@ function init() {
           if (!searchDom) return;
           searchDom.addEventListener('click', (event) => {
             const item = event.target.closest("div");
-            if (item) {
+            if (item && item.getAttribute('data-vars')) {
+              window.__chatModelFill?.(item.getAttribute('data-cmd'));
+            } else if (item) {
               const val = decodeURIComponent(item.getAttribute('data-prompt'));
               searchInput.value = val;
               document.querySelector('form textarea').focus();
@@ -138,9 +199,22 @@ async function cmdTip() {
       modelDom.style.bottom = '54px';
     }
 
-    const itemDom = (v) => `<div class="cmd-item" title="${v.prompt}" data-cmd="${v.cmd}" data-prompt="${encodeURIComponent(v.prompt)}"><b title="${v.cmd}">/${v.cmd}</b><i>${v.act}</i></div>`;
+    // Wraps the matched ranges of a field in `<mark>`.
+    const highlight = (v, field) => {
+      const text = v[field] ?? '';
+      const ranges = v.highlights?.find(h => h.field === field)?.ranges || [];
+      let html = '';
+      let last = 0;
+      for (const [start, end] of ranges) {
+        html += escapeHtml(text.slice(last, start)) + `<mark>${escapeHtml(text.slice(start, end))}</mark>`;
+        last = end;
+      }
+      return html + escapeHtml(text.slice(last));
+    };
+    const itemDom = (v) => `<div class="cmd-item" title="${escapeHtml(v.prompt)}" data-cmd="${v.cmd}" data-prompt="${encodeURIComponent(v.prompt)}"${v.vars?.length ? ' data-vars="1"' : ''}><b title="${v.cmd}">/${highlight(v, 'cmd')}</b><i>${highlight(v, 'act')}</i></div>`;
     const renderList = (v) => {
       initDom();
+      window.__cmd_hits = v;
       modelDom.innerHTML = `<div>${v.map(itemDom).join('')}</div>`;
       window.__CHAT_MODEL_CMD_PROMPT__ = v[0]?.prompt.trim();
       window.__CHAT_MODEL_CMD__ = v[0]?.cmd.trim();
@@ -155,6 +229,63 @@ async function cmdTip() {
     }
     const searchInput = document.querySelector('form textarea');
 
+    // Prompts with `{{variables}}` are filled in through a form and rendered by `render_prompt`.
+    const fillForm = (cmd) => {
+      const entry = (window.__cmd_hits || data).find(i => i.cmd === cmd);
+      if (!entry?.vars?.length) return false;
+      initDom();
+
+      document.querySelector('.chat-model-cmd-form')?.remove();
+      const formDom = document.createElement('div');
+      formDom.classList.add('chat-model-cmd-form');
+      document.querySelector('form').appendChild(formDom);
+      const field = (v) => {
+        const value = escapeHtml(v.default ?? '');
+        let input = `<input data-var="${v.name}" value="${value}" />`;
+        if (v.kind === 'long') input = `<textarea data-var="${v.name}" rows="3">${value}</textarea>`;
+        if (v.kind === 'number') input = `<input data-var="${v.name}" type="number" value="${value}" />`;
+        if (v.kind === 'choice') {
+          const options = v.options.map(o => `<option${o === v.default ? ' selected' : ''}>${escapeHtml(o)}</option>`);
+          input = `<select data-var="${v.name}">${options.join('')}</select>`;
+        }
+        return `<label data-field="${v.name}"><span>${v.name}</span>${input}</label>`;
+      };
+      formDom.innerHTML = `<div><b>/${entry.cmd}</b> <i>${escapeHtml(entry.act)}</i>${entry.vars.map(field).join('')}`
+        + '<div class="cmd-form-error"></div>'
+        + '<div class="cmd-form-actions"><button type="button" data-action="cancel">Cancel</button><button type="button" data-action="insert">Insert</button></div></div>';
+
+      const close = () => {
+        formDom.remove();
+        searchInput.focus();
+      };
+      const insert = async () => {
+        const values = {};
+        formDom.querySelectorAll('[data-var]').forEach((el) => { values[el.getAttribute('data-var')] = el.value; });
+        formDom.querySelectorAll('.invalid').forEach((el) => el.classList.remove('invalid'));
+        try {
+          searchInput.value = await invoke('render_prompt', { prompt: entry.prompt, values });
+          close();
+        } catch (err) {
+          formDom.querySelector('.cmd-form-error').textContent = err?.message || String(err);
+          (err?.fields || []).forEach((f) => formDom.querySelector(`[data-field="${f.field}"]`)?.classList.add('invalid'));
+        }
+      };
+      formDom.querySelector('[data-action="cancel"]').addEventListener('click', close);
+      formDom.querySelector('[data-action="insert"]').addEventListener('click', insert);
+      formDom.addEventListener('keydown', (event) => {
+        // keep Enter from submitting the chat form
+        if (event.keyCode === 13 && event.target.tagName !== 'TEXTAREA') {
+          event.preventDefault();
+          insert();
+        }
+        if (event.keyCode === 27) close(); // Escape
+        event.stopPropagation();
+      });
+      formDom.querySelector('[data-var]')?.focus();
+      return true;
+    };
+    window.__chatModelFill = fillForm;
+
     // Enter a command starting with `/` and press a space to automatically fill `chatgpt prompt`.
     // If more than one command appears in the search results, the first one will be used by default.
     function cmdKeydown(event) {
@@ -162,12 +293,19 @@ async function cmdTip() {
         return;
       }
 
+      // ------------------ Templates open the fill-in form (Space | Enter | TAB) -------------------
+      if ([9, 13, 32].includes(event.keyCode) && fillForm(window.__CHAT_MODEL_CMD__)) {
+        event.preventDefault();
+        return;
+      }
+
       // ------------------ Keyboard scrolling (ArrowUp | ArrowDown) --------------------------
       if (event.keyCode === 38 &&  window.__cmd_index > 0) { // ArrowUp
         window.__cmd_list[window.__cmd_index].classList.remove('selected');
         window.__cmd_index = window.__cmd_index - 1;
         window.__cmd_list[window.__cmd_index].classList.add('selected');
         window.__CHAT_MODEL_CMD_PROMPT__ = decodeURIComponent(window.__cmd_list[window.__cmd_index].getAttribute('data-prompt'));
+        window.__CHAT_MODEL_CMD__ = window.__cmd_list[window.__cmd_index].getAttribute('data-cmd');
         searchInput.value = `/${window.__cmd_list[window.__cmd_index].getAttribute('data-cmd')}`;
         event.preventDefault();
       }
@@ -177,6 +315,7 @@ async function cmdTip() {
         window.__cmd_index = window.__cmd_index + 1;
         window.__cmd_list[window.__cmd_index].classList.add('selected');
         window.__CHAT_MODEL_CMD_PROMPT__ = decodeURIComponent(window.__cmd_list[window.__cmd_index].getAttribute('data-prompt'));
+        window.__CHAT_MODEL_CMD__ = window.__cmd_list[window.__cmd_index].getAttribute('data-cmd');
         searchInput.value = `/${window.__cmd_list[window.__cmd_index].getAttribute('data-cmd')}`;
         event.preventDefault();
       }
@@ -243,7 +382,8 @@ async function cmdTip() {
     searchInput.removeEventListener('keydown', cmdKeydown, { capture: true });
     searchInput.addEventListener('keydown', cmdKeydown, { capture: true });
 
-    function cmdInput() {
+    // Matching is done by `search_prompts`; a late answer for an older query is dropped.
+    async function cmdInput() {
       if (searchInput.value === '') {
         initDom();
       }
@@ -256,13 +396,8 @@ async function cmdTip() {
         return;
       }
 
-      // all cmd result
-      if (query === '/') {
-        renderList(data);
-        return;
-      }
-
-      const result = data.filter(i => new RegExp(query.substring(1)).test(i.cmd));
+      const result = await invoke('search_prompts', { query, limit: 50 }).catch(() => []);
+      if (searchInput.value !== query || window.__CHAT_MODEL_STATUS__) return;
       if (result.length > 0) {
         renderList(result);
       } else {
@@ -274,6 +409,10 @@ async function cmdTip() {
   }
 }
 
+function escapeHtml(str) {
+  return String(str).replace(/[&<>"']/g, (c) => ({ '&': '&amp;', '<': '&lt;', '>': '&gt;', '"': '&quot;', "'": '&#39;' })[c]);
+}
+
 function initDom() {
   const modelDom = document.querySelector('.chat-model-cmd-list');
   if (modelDom) {
@@ -284,6 +423,7 @@ function initDom() {
   delete window.__CHAT_MODEL_STATUS__;
   delete window.__cmd_list;
   delete window.__cmd_index;
+  delete window.__cmd_hits;
 }
 
 if (
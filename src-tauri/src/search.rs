@@ -0,0 +1,157 @@
+// src-tauri/src/search.rs  — in-memory fuzzy index over the slash commands, backing the cmd.js palette
+
+use std::cmp::Reverse;
+use std::sync::{Arc, RwLock};
+
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use serde::Serialize;
+
+use crate::cmd_index::{self, CmdEntry};
+
+pub const DEFAULT_LIMIT: usize = 50;
+/// Only the start of a prompt body is searched; long prompts would otherwise
+/// match almost any query.
+const PROMPT_CHARS: usize = 500;
+/// A command that starts with the query always ranks above fuzzy hits.
+const PREFIX_BONUS: i64 = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Field {
+  Cmd,
+  Act,
+  Tags,
+  Prompt,
+}
+
+impl Field {
+  /// Multiplies the matcher score, so a hit in the command outranks one in the body.
+  fn weight(self) -> i64 {
+    match self {
+      Field::Cmd => 4,
+      Field::Act => 3,
+      Field::Tags => 2,
+      Field::Prompt => 1,
+    }
+  }
+}
+
+/// Matched spans of one field as `[start, end)` UTF-16 offsets, ready for
+/// `String.prototype.slice`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Highlight {
+  pub field: Field,
+  pub ranges: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+  #[serde(flatten)]
+  pub entry: CmdEntry,
+  pub score: i64,
+  pub highlights: Vec<Highlight>,
+}
+
+// ---------- Index ----------
+struct Doc {
+  entry: CmdEntry,
+  /// Searchable text per field; tags are joined by spaces.
+  texts: Vec<(Field, String)>,
+}
+
+static INDEX: RwLock<Option<Arc<Vec<Doc>>>> = RwLock::new(None);
+
+fn doc(entry: CmdEntry) -> Doc {
+  let prompt: String = entry.record.prompt.chars().take(PROMPT_CHARS).collect();
+  let texts = vec![
+    (Field::Cmd, entry.record.cmd.clone()),
+    (Field::Act, entry.record.act.clone()),
+    (Field::Tags, entry.record.tags.join(" ")),
+    (Field::Prompt, prompt),
+  ];
+  Doc { entry, texts }
+}
+
+/// Replaces the index with `entries`, called whenever `chat.model.cmd.json` is rebuilt.
+pub fn reindex(entries: &[CmdEntry]) {
+  store(entries);
+}
+
+fn store(entries: &[CmdEntry]) -> Arc<Vec<Doc>> {
+  let docs = Arc::new(entries.iter().cloned().map(doc).collect::<Vec<_>>());
+  if let Ok(mut index) = INDEX.write() {
+    *index = Some(docs.clone());
+  }
+  docs
+}
+
+/// Built from `chat.model.cmd.json` on first use.
+fn index() -> Result<Arc<Vec<Doc>>, String> {
+  if let Some(docs) = INDEX.read().map_err(|e| e.to_string())?.as_ref() {
+    return Ok(docs.clone());
+  }
+  Ok(store(&cmd_index::get_chat_model_cmd()?.data))
+}
+
+// ---------- Matching ----------
+/// Char indices from the matcher, merged into UTF-16 ranges.
+fn ranges(text: &str, indices: &[usize]) -> Vec<(usize, usize)> {
+  let mut utf16 = Vec::with_capacity(text.len() + 1);
+  let mut offset = 0;
+  for c in text.chars() {
+    utf16.push(offset);
+    offset += c.len_utf16();
+  }
+  utf16.push(offset);
+
+  let mut ranges: Vec<(usize, usize)> = vec![];
+  for &i in indices {
+    let (start, end) = (utf16[i], utf16[i + 1]);
+    match ranges.last_mut() {
+      Some(last) if last.1 == start => last.1 = end,
+      _ => ranges.push((start, end)),
+    }
+  }
+  ranges
+}
+
+fn score(matcher: &SkimMatcherV2, doc: &Doc, query: &str) -> Option<SearchHit> {
+  let mut total = 0;
+  let mut highlights = vec![];
+  for (field, text) in &doc.texts {
+    let Some((score, indices)) = matcher.fuzzy_indices(text, query) else { continue };
+    total = total.max(score * field.weight());
+    highlights.push(Highlight { field: *field, ranges: ranges(text, &indices) });
+  }
+  if highlights.is_empty() {
+    return None;
+  }
+  if doc.entry.record.cmd.starts_with(&query.to_lowercase()) {
+    total += PREFIX_BONUS;
+  }
+  Some(SearchHit { entry: doc.entry.clone(), score: total, highlights })
+}
+
+/// Best hits first; ties keep index order, so precedence still decides.
+pub fn search(query: &str, limit: usize) -> Result<Vec<SearchHit>, String> {
+  let docs = index()?;
+  let query = query.trim().trim_start_matches('/');
+  if query.is_empty() {
+    let hits = docs.iter().take(limit);
+    return Ok(hits.map(|d| SearchHit { entry: d.entry.clone(), score: 0, highlights: vec![] }).collect());
+  }
+
+  let matcher = SkimMatcherV2::default().ignore_case();
+  let mut hits: Vec<SearchHit> = docs.iter().filter_map(|d| score(&matcher, d, query)).collect();
+  hits.sort_by_key(|hit| Reverse(hit.score));
+  hits.truncate(limit);
+  Ok(hits)
+}
+
+// ---------- Tauri commands ----------
+/// A leading `/` is ignored; an empty query lists the first `limit` commands.
+#[tauri::command]
+pub fn search_prompts(query: String, limit: Option<usize>) -> Result<Vec<SearchHit>, String> {
+  search(&query, limit.unwrap_or(DEFAULT_LIMIT))
+}
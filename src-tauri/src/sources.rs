@@ -0,0 +1,462 @@
+// src-tauri/src/sources.rs  — user prompt sources (local / http / https, csv / json / dir) from chat.model.json
+
+use std::{
+  collections::{HashMap, HashSet},
+  fs,
+  path::{Component, Path, PathBuf},
+  sync::mpsc,
+  time::Duration,
+};
+
+use minisign_verify::{PublicKey, Signature};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::cmd_index::{self, CHAT_MODEL_CMD_JSON};
+use crate::conf::now_ms;
+use crate::history::{self, HISTORY_DIR};
+use crate::parser::{self, ParseWarning, ParsedPrompts};
+use crate::prompts::{self, PromptRecord, CACHE_MODEL_DIR, CHAT_MODEL_JSON, CHAT_PROMPTS_CSV};
+use crate::utils::{chat_cache_dir, chat_root, create_file};
+
+pub const USER_SYNC_TAG: &str = "user-sync";
+/// Quiet time after a change in a `dir` source before it is re-read.
+const DIR_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// A row of `chat.model.json` → `sync_custom`, as written by the SyncCustom form.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptSource {
+  pub id: String,
+  #[serde(default)]
+  pub name: String,
+  /// `local`, `http` or `https`
+  pub protocol: String,
+  /// Without the extension; relative to `chat_root()` for `local`.
+  pub path: String,
+  /// `csv`, `json`, or `dir` for a local directory of Markdown prompts
+  pub ext: String,
+  /// Hex SHA-256 the payload must hash to.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub sha256: Option<String>,
+  /// Base64 minisign public key; the signature is read from `<payload>.minisig`.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub minisign_key: Option<String>,
+  #[serde(flatten)]
+  pub rest: serde_json::Map<String, Value>,
+}
+
+enum Location {
+  File(PathBuf),
+  Dir(PathBuf),
+  Url(String),
+}
+
+/// The `sha256` / `minisign_key` pins of a source, trimmed. Cached payloads
+/// and HTTP validators are only trusted under the pins they were accepted with.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pins {
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub sha256: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub minisign_key: Option<String>,
+}
+
+impl PromptSource {
+  pub fn pins(&self) -> Pins {
+    Pins {
+      sha256: pin(&self.sha256).map(str::to_ascii_lowercase),
+      minisign_key: pin(&self.minisign_key).map(String::from),
+    }
+  }
+
+  /// The URL for `http` / `https` sources, `None` for local files.
+  pub fn remote_url(&self) -> Option<String> {
+    match self.location() {
+      Ok(Location::Url(url)) => Some(url),
+      _ => None,
+    }
+  }
+
+  /// Mirrors `getPath` in SyncCustom/config.tsx.
+  fn location(&self) -> Result<Location, String> {
+    match self.protocol.as_str() {
+      "local" if self.ext == "dir" => {
+        let dir = normalize(&chat_root().join(&self.path));
+        // the app writes below chat_root() on every sync, which would re-trigger it forever
+        if chat_root().starts_with(&dir) {
+          return Err(format!("{} contains the data directory and cannot be a dir source", dir.display()));
+        }
+        Ok(Location::Dir(dir))
+      }
+      "local" => Ok(Location::File(chat_root().join(format!("{}.{}", self.path, self.ext)))),
+      _ if self.ext == "dir" => Err("a dir source has to be local".into()),
+      "http" | "https" => Ok(Location::Url(format!("{}://{}.{}", self.protocol, self.path, self.ext))),
+      other => Err(format!("unsupported protocol \"{other}\"")),
+    }
+  }
+}
+
+/// Resolves `.` and `..` without touching the filesystem.
+fn normalize(path: &Path) -> PathBuf {
+  let mut out = PathBuf::new();
+  for c in path.components() {
+    match c {
+      Component::CurDir => {}
+      Component::ParentDir => {
+        out.pop();
+      }
+      c => out.push(c),
+    }
+  }
+  out
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SourceError {
+  #[error("{0}")]
+  Load(String),
+  /// The payload did not match the source's pin and was not used.
+  #[error("integrity check failed: {0}")]
+  Integrity(String),
+}
+
+impl From<String> for SourceError {
+  fn from(e: String) -> Self {
+    SourceError::Load(e)
+  }
+}
+
+/// Per-source outcome, also stored on the source as `last_status` / `last_error`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceStatus {
+  pub id: String,
+  pub name: String,
+  pub ok: bool,
+  pub count: usize,
+  /// Set when the fetch failed and the last good copy from the cache was used.
+  pub from_cache: bool,
+  /// Set when the payload failed its `sha256` / `minisign_key` pin.
+  pub untrusted: bool,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub error: Option<String>,
+  pub last_updated: Option<u64>,
+  /// Rows that were skipped or repaired while parsing.
+  pub warnings: Vec<ParseWarning>,
+}
+
+// ---------- Cache (last good result per source id) ----------
+#[derive(Deserialize)]
+struct Cached {
+  /// The pins the records were verified against.
+  #[serde(default)]
+  pins: Pins,
+  records: Vec<PromptRecord>,
+}
+
+fn cache_path(id: &str) -> PathBuf {
+  chat_cache_dir().join("sources").join(format!("{id}.json"))
+}
+
+fn write_cached(source: &PromptSource, records: &[PromptRecord]) {
+  let cached = json!({ "pins": source.pins(), "records": records });
+  if let Ok(bytes) = serde_json::to_vec(&cached) {
+    let _ = create_file(cache_path(&source.id), bytes);
+  }
+}
+
+/// The last good copy, as long as it was verified against the pins the
+/// source has now; a copy from before a pin was set or changed is dropped.
+fn read_cached(source: &PromptSource) -> Option<Vec<PromptRecord>> {
+  let cached: Cached = serde_json::from_slice(&fs::read(cache_path(&source.id)).ok()?).ok()?;
+  if cached.pins != source.pins() {
+    forget_cached(&source.id);
+    return None;
+  }
+  Some(cached.records)
+}
+
+/// Drops the last good copy of a source, so a failing sync has nothing to fall back to.
+pub fn forget_cached(id: &str) {
+  let path = cache_path(id);
+  if let Err(e) = fs::remove_file(&path)
+    && e.kind() != std::io::ErrorKind::NotFound
+  {
+    eprintln!("Failed to remove {}: {e}", path.display());
+  }
+}
+
+// ---------- Integrity ----------
+fn pin(value: &Option<String>) -> Option<&str> {
+  value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// Checks `bytes` against the pins set on `source`, if any.
+pub async fn verify(source: &PromptSource, bytes: &[u8]) -> Result<(), SourceError> {
+  let integrity = |e: String| SourceError::Integrity(e);
+  if let Some(expected) = pin(&source.sha256) {
+    let actual = format!("{:x}", Sha256::digest(bytes));
+    if !actual.eq_ignore_ascii_case(expected) {
+      return Err(integrity(format!("SHA-256 is {actual}, the source is pinned to {expected}")));
+    }
+  }
+
+  let Some(key) = pin(&source.minisign_key) else { return Ok(()) };
+  let key = PublicKey::from_base64(key).map_err(|e| integrity(format!("invalid minisign public key: {e}")))?;
+  let signature = match source.location()? {
+    Location::File(path) => {
+      let path = PathBuf::from(format!("{}.minisig", path.display()));
+      fs::read(&path).map_err(|e| integrity(format!("{}: {e}", path.display())))?
+    }
+    Location::Url(url) => prompts::fetch(&format!("{url}.minisig"))
+      .await
+      .map_err(|e| integrity(format!("cannot fetch the signature: {e}")))?,
+    Location::Dir(_) => return Err(integrity("dir sources cannot be signed".into())),
+  };
+  let signature = Signature::decode(&String::from_utf8_lossy(&signature))
+    .map_err(|e| integrity(format!("invalid minisign signature: {e}")))?;
+  key.verify(bytes, &signature, false).map_err(|_| integrity("the minisign signature does not match".into()))
+}
+
+// ---------- Sync ----------
+async fn load(source: &PromptSource) -> Result<ParsedPrompts, SourceError> {
+  let bytes = match source.location()? {
+    Location::Dir(_) if pin(&source.sha256).is_some() || pin(&source.minisign_key).is_some() => {
+      return Err(SourceError::Integrity("dir sources cannot be pinned".into()));
+    }
+    Location::Dir(dir) => return Ok(parser::parse_dir(&dir, USER_SYNC_TAG)?),
+    Location::File(path) => fs::read(&path).map_err(|e| format!("{}: {e}", path.display()))?,
+    Location::Url(url) => prompts::fetch(&url).await.map_err(|e| e.to_string())?,
+  };
+  verified(source, &bytes).await
+}
+
+/// `verify`, then `parse`.
+pub async fn verified(source: &PromptSource, bytes: &[u8]) -> Result<ParsedPrompts, SourceError> {
+  verify(source, bytes).await?;
+  Ok(parse(source, bytes)?)
+}
+
+pub fn parse(source: &PromptSource, bytes: &[u8]) -> Result<ParsedPrompts, String> {
+  match source.ext.as_str() {
+    "json" => parser::parse_json(bytes, USER_SYNC_TAG),
+    "csv" => Ok(parser::parse_csv(bytes, USER_SYNC_TAG)),
+    other => Err(format!("unsupported format \"{other}\"")),
+  }
+}
+
+/// Resolves one source and refreshes its record list in `cache_model/`.
+pub async fn sync_source(source: &PromptSource) -> SourceStatus {
+  apply(source, load(source).await)
+}
+
+/// Stores a load result for `source`, keeping the enable flags the user set in
+/// SyncRecord; prompts new to the source start with the flag they were parsed
+/// with. A failed load falls back to the last good copy, except for a payload
+/// that failed verification: then the source is emptied and nothing of it is served.
+pub fn apply(source: &PromptSource, loaded: Result<ParsedPrompts, SourceError>) -> SourceStatus {
+  let root = chat_root();
+  let (mut warnings, mut error, mut untrusted) = (vec![], None, false);
+  let (result, from_cache) = match loaded {
+    Ok(parsed) => {
+      write_cached(source, &parsed.records);
+      warnings = parsed.warnings;
+      (Some(parsed.records), false)
+    }
+    Err(e @ SourceError::Integrity(_)) => {
+      untrusted = true;
+      error = Some(e.to_string());
+      forget_cached(&source.id);
+      (Some(vec![]), false)
+    }
+    Err(e) => {
+      error = Some(e.to_string());
+      let cached = read_cached(source);
+      let from_cache = cached.is_some();
+      (cached, from_cache)
+    }
+  };
+
+  let mut status = SourceStatus {
+    id: source.id.clone(),
+    name: source.name.clone(),
+    ok: result.is_some() && !untrusted,
+    count: 0,
+    from_cache,
+    untrusted,
+    error,
+    last_updated: None,
+    warnings,
+  };
+  let Some(mut records) = result else { return status };
+
+  let before = prompts::read_cache(&root, &source.id);
+  let enabled: HashMap<&str, bool> = before.iter().map(|r| (r.act.as_str(), r.enable)).collect();
+  for r in &mut records {
+    r.enable = enabled.get(r.act.as_str()).copied().unwrap_or(r.enable);
+  }
+  if let Err(e) = prompts::write_cache(&root, &source.id, &records) {
+    status.ok = false;
+    status.error = Some(e.to_string());
+    return status;
+  }
+  let name = if source.name.is_empty() { &source.id } else { &source.name };
+  if let Err(e) = history::record(&root, &source.id, name, &before, &records, None) {
+    eprintln!("Failed to record the sync history of {}: {e}", source.id);
+  }
+
+  status.count = records.len();
+  if status.ok && !from_cache {
+    status.last_updated = Some(now_ms());
+  }
+  status
+}
+
+pub fn list() -> Vec<PromptSource> {
+  fs::read(chat_root().join(CHAT_MODEL_JSON))
+    .ok()
+    .and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok())
+    .and_then(|model| serde_json::from_value(model.get("sync_custom")?.clone()).ok())
+    .unwrap_or_default()
+}
+
+/// Writes each source's outcome back into `chat.model.json` for the SyncCustom table.
+pub fn record_statuses(statuses: &[SourceStatus]) -> anyhow::Result<()> {
+  // read inside the update, so a source added meanwhile is not written away
+  prompts::update_model(&chat_root(), |model| {
+    let mut sources: Vec<PromptSource> = model
+      .get("sync_custom")
+      .and_then(|v| serde_json::from_value(v.clone()).ok())
+      .unwrap_or_default();
+    for source in &mut sources {
+      let Some(status) = statuses.iter().find(|s| s.id == source.id) else { continue };
+      let last_status = match status {
+        s if s.untrusted => "untrusted",
+        s if s.from_cache => "stale",
+        s if s.ok => "ok",
+        _ => "error",
+      };
+      source.rest.insert("last_status".into(), json!(last_status));
+      source.rest.insert("last_error".into(), json!(status.error));
+      if let Some(ts) = status.last_updated {
+        source.rest.insert("last_updated".into(), json!(ts));
+      }
+    }
+    model["sync_custom"] = serde_json::to_value(sources)?;
+    Ok(())
+  })
+}
+
+// ---------- Watching `dir` sources ----------
+/// The directory of every local `dir` source, by source id.
+fn watched_dirs() -> HashMap<String, PathBuf> {
+  list()
+    .into_iter()
+    .filter_map(|s| match s.location() {
+      Ok(Location::Dir(dir)) => Some((s.id, dir)),
+      _ => None,
+    })
+    .collect()
+}
+
+/// Files and folders the app itself writes while syncing; changes there never
+/// come from a user editing a `dir` source.
+fn is_own_write(root: &Path, path: &Path) -> bool {
+  let Ok(rel) = path.strip_prefix(root) else { return path.starts_with(chat_cache_dir()) };
+  let Some(first) = rel.components().next().map(|c| c.as_os_str().to_string_lossy()) else { return false };
+  let own = [CHAT_MODEL_JSON, CHAT_MODEL_CMD_JSON, CHAT_PROMPTS_CSV, CACHE_MODEL_DIR, HISTORY_DIR];
+  // create_file's temp files are `.<name>.<pid>.<seq>.tmp`
+  own.iter().any(|o| first == *o || first.starts_with(&format!(".{o}.")))
+}
+
+/// Moves the recursive watches to the current set of `dir` sources.
+fn rearm(watcher: &mut RecommendedWatcher, dirs: &mut HashMap<String, PathBuf>) {
+  let next = watched_dirs();
+  for (id, dir) in dirs.iter() {
+    if next.get(id) != Some(dir) {
+      let _ = watcher.unwatch(dir);
+    }
+  }
+  for (id, dir) in &next {
+    if dirs.get(id) != Some(dir)
+      && let Err(e) = watcher.watch(dir, RecursiveMode::Recursive)
+    {
+      eprintln!("Failed to watch {}: {e}", dir.display());
+    }
+  }
+  *dirs = next;
+}
+
+/// Re-syncs a `dir` source whenever a file below it changes, so edits reach
+/// the slash palette without a manual sync. `chat.model.json` is watched as
+/// well to follow sources being added or removed.
+pub fn watch_dirs() {
+  let (tx, rx) = mpsc::channel::<Vec<PathBuf>>();
+  let watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+    let Ok(event) = res else { return };
+    if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) {
+      let _ = tx.send(event.paths);
+    }
+  });
+  let mut watcher = match watcher {
+    Ok(watcher) => watcher,
+    Err(e) => return eprintln!("Failed to watch the prompt directories: {e}"),
+  };
+  if let Err(e) = watcher.watch(&chat_root(), RecursiveMode::NonRecursive) {
+    return eprintln!("Failed to watch {}: {e}", chat_root().display());
+  }
+
+  // The thread owns the watcher, which owns the sender: both live as long as the app.
+  std::thread::spawn(move || {
+    let mut dirs: HashMap<String, PathBuf> = HashMap::new();
+    rearm(&mut watcher, &mut dirs);
+
+    while let Ok(mut paths) = rx.recv() {
+      while let Ok(more) = rx.recv_timeout(DIR_DEBOUNCE) {
+        paths.extend(more);
+      }
+      if paths.iter().any(|p| p.file_name().is_some_and(|n| n == CHAT_MODEL_JSON)) {
+        rearm(&mut watcher, &mut dirs);
+      }
+
+      let root = chat_root();
+      let changed: HashSet<&String> = dirs
+        .iter()
+        .filter(|(_, dir)| paths.iter().any(|p| p.starts_with(dir) && !is_own_write(&root, p)))
+        .map(|(id, _)| id)
+        .collect();
+      if changed.is_empty() {
+        continue;
+      }
+      let statuses: Vec<SourceStatus> = list()
+        .iter()
+        .filter(|s| changed.contains(&s.id))
+        .map(|s| tauri::async_runtime::block_on(sync_source(s)))
+        .collect();
+      if let Err(e) = record_statuses(&statuses) {
+        eprintln!("Failed to record prompt source statuses: {e}");
+      }
+      if let Err(e) = cmd_index::rebuild() {
+        eprintln!("Failed to rebuild chat.model.cmd.json: {e}");
+      }
+    }
+  });
+}
+
+// ---------- Tauri commands ----------
+/// Syncs the given source ids, or every registered source when `ids` is omitted.
+#[tauri::command]
+pub async fn sync_user_prompts(ids: Option<Vec<String>>) -> Result<Vec<SourceStatus>, String> {
+  let sources: Vec<_> = list()
+    .into_iter()
+    .filter(|s| ids.as_ref().is_none_or(|ids| ids.contains(&s.id)))
+    .collect();
+
+  let mut statuses = vec![];
+  for source in &sources {
+    statuses.push(sync_source(source).await);
+  }
+  record_statuses(&statuses).map_err(|e| e.to_string())?;
+  Ok(statuses)
+}
@@ -0,0 +1,167 @@
+// src-tauri/src/storage.rs  — exported files (downloads, notes) kept under chat_root(), with their index files
+
+use std::{
+  collections::HashMap,
+  fs,
+  path::{Component, Path, PathBuf},
+  sync::Mutex,
+};
+
+use serde::{ser::Serializer, Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::fs_extra;
+use crate::utils::{chat_root, create_file};
+
+pub const DOWNLOAD_JSON: &str = "chat.download.json";
+pub const NOTES_JSON: &str = "chat.notes.json";
+
+/// Index file of each directory whose files are listed in the Download and
+/// Notes views.
+const INDEXES: &[(&str, &str)] = &[("download", DOWNLOAD_JSON), ("notes", NOTES_JSON)];
+
+/// Index files are read, changed and written back under this lock.
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+  #[error("\"{0}\" is not a relative path inside the data directory")]
+  Path(String),
+  #[error("\"{0}\" is not a file index, use {DOWNLOAD_JSON} with download or {NOTES_JSON} with notes")]
+  Index(String),
+  #[error(transparent)]
+  Io(#[from] std::io::Error),
+  #[error(transparent)]
+  Json(#[from] serde_json::Error),
+  #[error(transparent)]
+  Metadata(#[from] fs_extra::Error),
+}
+
+impl Serialize for StorageError {
+  fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    serializer.serialize_str(self.to_string().as_ref())
+  }
+}
+
+type Result<T> = std::result::Result<T, StorageError>;
+
+/// One row of `chat.download.json` / `chat.notes.json`; `id` is the file stem.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileEntry {
+  pub id: String,
+  /// Shown in the views and renamed there; defaults to `id`.
+  pub name: String,
+  pub ext: String,
+  pub created: u64,
+}
+
+// ---------- Paths ----------
+/// `name` joined onto `root`, refusing absolute paths and anything but plain
+/// path segments, so nothing is written outside `root`.
+pub fn resolve(root: &Path, name: &str) -> Result<PathBuf> {
+  let path = Path::new(name);
+  let plain = path.components().all(|c| matches!(c, Component::Normal(_)));
+  if name.trim().is_empty() || !plain {
+    return Err(StorageError::Path(name.into()));
+  }
+  Ok(root.join(path))
+}
+
+/// Index file that lists `name`, if any.
+fn index_of(name: &str) -> Option<&'static str> {
+  let top = Path::new(name).components().next()?.as_os_str().to_str()?;
+  INDEXES.iter().find(|(dir, _)| *dir == top).map(|(_, index)| *index)
+}
+
+fn entry(path: &Path) -> Result<Option<FileEntry>> {
+  let (Some(id), Some(ext)) = (path.file_stem(), path.extension()) else { return Ok(None) };
+  let metadata = fs_extra::read_metadata(path)?;
+  // not every filesystem keeps a creation time
+  let created = match metadata.created_at_ms {
+    0 => metadata.modified_at_ms,
+    ms => ms,
+  };
+  let id = id.to_string_lossy().to_string();
+  Ok(Some(FileEntry { name: id.clone(), id, ext: ext.to_string_lossy().to_string(), created }))
+}
+
+// ---------- Index ----------
+fn read_index(root: &Path, index: &str) -> Vec<FileEntry> {
+  fs::read(root.join(index))
+    .ok()
+    .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+    .unwrap_or_default()
+}
+
+/// Rebuilds `index` from the files under `dir`, newest first. Names given in
+/// the views survive; `rename` sets the name of one entry.
+fn rescan(root: &Path, dir: &str, index: &str, rename: Option<(&str, &str)>) -> Result<Vec<FileEntry>> {
+  let names: HashMap<String, String> = read_index(root, index).into_iter().map(|e| (e.id, e.name)).collect();
+  let mut entries = vec![];
+  let walker = WalkDir::new(root.join(dir)).into_iter().filter_entry(|e| !e.file_name().to_string_lossy().starts_with('.'));
+  for file in walker.flatten().filter(|e| e.file_type().is_file()) {
+    let Some(mut entry) = entry(file.path())? else { continue };
+    if let Some(name) = names.get(&entry.id) {
+      entry.name = name.clone();
+    }
+    if let Some((id, name)) = rename
+      && id == entry.id
+      && !name.trim().is_empty()
+    {
+      entry.name = name.trim().into();
+    }
+    entries.push(entry);
+  }
+  entries.sort_by_key(|e| std::cmp::Reverse(e.created));
+  create_file(root.join(index), serde_json::to_vec_pretty(&entries)?)?;
+  Ok(entries)
+}
+
+// ---------- Writing ----------
+/// Writes `bytes` to `name` under `root` atomically and, for files under
+/// `download/` or `notes/`, updates the matching index in the same call.
+pub fn store(root: &Path, name: &str, bytes: &[u8], filename: Option<&str>) -> Result<Option<FileEntry>> {
+  let path = resolve(root, name)?;
+  let _guard = INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+  create_file(&path, bytes)?;
+
+  let Some(index) = index_of(name) else { return Ok(None) };
+  let Some(mut written) = entry(&path)? else { return Ok(None) };
+  let mut entries: Vec<FileEntry> = read_index(root, index).into_iter().filter(|e| e.id != written.id).collect();
+  if let Some(name) = filename.map(str::trim).filter(|n| !n.is_empty()) {
+    written.name = name.into();
+  }
+  entries.insert(0, written.clone());
+  create_file(root.join(index), serde_json::to_vec_pretty(&entries)?)?;
+  Ok(Some(written))
+}
+
+// ---------- Tauri commands ----------
+/// Binary export (PNG, PDF) at `name`, e.g. `download/img/<id>.png`.
+#[tauri::command]
+pub fn download(name: String, blob: Vec<u8>, filename: Option<String>) -> Result<Option<FileEntry>> {
+  store(&chat_root(), &name, &blob, filename.as_deref())
+}
+
+/// Text export (Markdown) at `name`, e.g. `notes/<id>.md`.
+#[tauri::command]
+pub fn save_file(name: String, content: String, filename: Option<String>) -> Result<Option<FileEntry>> {
+  store(&chat_root(), &name, content.as_bytes(), filename.as_deref())
+}
+
+/// Re-reads `dir` into its index `pathname`, dropping entries whose file is
+/// gone; `filename` renames the entry `id`.
+#[tauri::command]
+pub fn download_list(pathname: String, dir: String, filename: Option<String>, id: Option<String>) -> Result<Vec<FileEntry>> {
+  let (dir, index) = INDEXES
+    .iter()
+    .copied()
+    .find(|(d, index)| *d == dir && *index == pathname)
+    .ok_or(StorageError::Index(pathname))?;
+  let rename = id.as_deref().zip(filename.as_deref());
+  let _guard = INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+  rescan(&chat_root(), dir, index, rename)
+}
@@ -0,0 +1,298 @@
+// src-tauri/src/template.rs  — `{{variable}}` prompt templates: parsing, type hints and rendering
+
+use std::collections::HashMap;
+
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::conf::FieldError;
+
+/// How the fill-in form asks for a value: `{{name}}`, `{{name:long}}`,
+/// `{{name:number}}` or a choice `{{name:rust|go|python}}`. Any of them can
+/// end in `=default`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VarKind {
+  Text,
+  Long,
+  Number,
+  Choice,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateVar {
+  pub name: String,
+  pub kind: VarKind,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub default: Option<String>,
+  /// Allowed values of a `choice`.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub options: Vec<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TemplateError {
+  #[error("template error at character {offset}: {message}")]
+  Syntax { offset: usize, message: String },
+  #[error("undefined variables: {}", .0.iter().map(|e| format!("{{{{{}}}}}", e.field)).collect::<Vec<_>>().join(", "))]
+  Undefined(Vec<FieldError>),
+  #[error("{}", .0.iter().map(|e| format!("{}: {}", e.field, e.message)).collect::<Vec<_>>().join("; "))]
+  Invalid(Vec<FieldError>),
+}
+
+impl TemplateError {
+  pub fn kind(&self) -> &'static str {
+    match self {
+      TemplateError::Syntax { .. } => "syntax",
+      TemplateError::Undefined(_) => "undefined",
+      TemplateError::Invalid(_) => "invalid",
+    }
+  }
+}
+
+impl Serialize for TemplateError {
+  fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    let fields: &[FieldError] = match self {
+      TemplateError::Undefined(fields) | TemplateError::Invalid(fields) => fields,
+      TemplateError::Syntax { .. } => &[],
+    };
+    let mut s = serializer.serialize_struct("TemplateError", 3)?;
+    s.serialize_field("kind", self.kind())?;
+    s.serialize_field("message", &self.to_string())?;
+    s.serialize_field("fields", fields)?;
+    s.end()
+  }
+}
+
+// ---------- Parsing ----------
+enum Piece {
+  Text(String),
+  /// Index into `Template::vars`.
+  Var(usize),
+}
+
+pub struct Template {
+  pieces: Vec<Piece>,
+  pub vars: Vec<TemplateVar>,
+}
+
+fn syntax(offset: usize, message: impl Into<String>) -> TemplateError {
+  TemplateError::Syntax { offset, message: message.into() }
+}
+
+fn valid_name(name: &str) -> bool {
+  let mut chars = name.chars();
+  chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+    && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// `name[:type][=default]`, the text between the braces.
+fn parse_var(inner: &str, offset: usize) -> Result<TemplateVar, TemplateError> {
+  let (head, default) = match inner.split_once('=') {
+    Some((head, default)) => (head, Some(default.trim().to_string())),
+    None => (inner, None),
+  };
+  let (name, hint) = match head.split_once(':') {
+    Some((name, hint)) => (name.trim(), Some(hint.trim())),
+    None => (head.trim(), None),
+  };
+  if !valid_name(name) {
+    return Err(syntax(offset, format!("\"{name}\" is not a valid variable name")));
+  }
+
+  let (kind, options) = match hint {
+    None | Some("text") => (VarKind::Text, vec![]),
+    Some("long") => (VarKind::Long, vec![]),
+    Some("number") => (VarKind::Number, vec![]),
+    Some(hint) if hint.contains('|') => {
+      let options: Vec<String> = hint.split('|').map(|o| o.trim().to_string()).filter(|o| !o.is_empty()).collect();
+      (VarKind::Choice, options)
+    }
+    Some(hint) => {
+      return Err(syntax(offset, format!("unknown type \"{hint}\" for {name}, use text, long, number or a|b|c")));
+    }
+  };
+  let var = TemplateVar { name: name.into(), kind, default, options };
+  if let Some(default) = &var.default
+    && let Err(message) = check(&var, default)
+  {
+    return Err(syntax(offset, format!("default of {name} {message}")));
+  }
+  Ok(var)
+}
+
+/// Type check of one value against its hint.
+fn check(var: &TemplateVar, value: &str) -> Result<(), String> {
+  match var.kind {
+    VarKind::Number if value.parse::<f64>().is_err() => Err(format!("must be a number, got \"{value}\"")),
+    VarKind::Choice if !var.options.iter().any(|o| o == value) => {
+      Err(format!("must be one of {}", var.options.join(", ")))
+    }
+    _ => Ok(()),
+  }
+}
+
+/// `\{{` is a literal `{{`. A variable used more than once is declared by its
+/// first occurrence; later ones may repeat the same hint or leave it out.
+pub fn parse(prompt: &str) -> Result<Template, TemplateError> {
+  let mut pieces = vec![];
+  let mut vars: Vec<TemplateVar> = vec![];
+  let mut text = String::new();
+  let mut rest = prompt;
+
+  while let Some(start) = rest.find("{{") {
+    let offset = prompt.len() - rest.len() + start;
+    if rest[..start].ends_with('\\') {
+      text.push_str(&rest[..start - 1]);
+      text.push_str("{{");
+      rest = &rest[start + 2..];
+      continue;
+    }
+    text.push_str(&rest[..start]);
+    let Some(len) = rest[start + 2..].find("}}") else {
+      return Err(syntax(offset, "\"{{\" is never closed"));
+    };
+    let inner = &rest[start + 2..start + 2 + len];
+    let var = parse_var(inner, offset)?;
+    rest = &rest[start + 2 + len + 2..];
+
+    let bare = !inner.contains([':', '=']);
+    let index = match vars.iter().position(|v| v.name == var.name) {
+      Some(i) if bare || vars[i] == var => i,
+      Some(_) => return Err(syntax(offset, format!("{} is declared again with a different type or default", var.name))),
+      None => {
+        vars.push(var);
+        vars.len() - 1
+      }
+    };
+    if !text.is_empty() {
+      pieces.push(Piece::Text(std::mem::take(&mut text)));
+    }
+    pieces.push(Piece::Var(index));
+  }
+  text.push_str(rest);
+  if !text.is_empty() {
+    pieces.push(Piece::Text(text));
+  }
+  Ok(Template { pieces, vars })
+}
+
+impl Template {
+  /// Blank values fall back to the default. Every variable left without a
+  /// value is reported at once, as is every value that fails its type hint.
+  pub fn render(&self, values: &HashMap<String, String>) -> Result<String, TemplateError> {
+    let mut resolved = Vec::with_capacity(self.vars.len());
+    let (mut undefined, mut invalid) = (vec![], vec![]);
+    for var in &self.vars {
+      let value = values.get(&var.name).map(|v| v.trim()).filter(|v| !v.is_empty()).or(var.default.as_deref());
+      match value {
+        None => undefined.push(FieldError { field: var.name.clone(), message: "has no value and no default".into() }),
+        Some(value) => {
+          if let Err(message) = check(var, value) {
+            invalid.push(FieldError { field: var.name.clone(), message });
+          }
+          resolved.push(value);
+        }
+      }
+    }
+    if !undefined.is_empty() {
+      return Err(TemplateError::Undefined(undefined));
+    }
+    if !invalid.is_empty() {
+      return Err(TemplateError::Invalid(invalid));
+    }
+
+    Ok(self
+      .pieces
+      .iter()
+      .map(|piece| match piece {
+        Piece::Text(text) => text.as_str(),
+        Piece::Var(i) => resolved[*i],
+      })
+      .collect())
+  }
+}
+
+// ---------- Tauri commands ----------
+/// Variables of a prompt, in order of first use.
+#[tauri::command]
+pub fn prompt_vars(prompt: String) -> Result<Vec<TemplateVar>, TemplateError> {
+  Ok(parse(&prompt)?.vars)
+}
+
+#[tauri::command]
+pub fn render_prompt(prompt: String, values: Option<HashMap<String, String>>) -> Result<String, TemplateError> {
+  parse(&prompt)?.render(&values.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+  }
+
+  fn syntax_offset(prompt: &str) -> usize {
+    match parse(prompt) {
+      Err(TemplateError::Syntax { offset, .. }) => offset,
+      Err(e) => panic!("{prompt}: expected a syntax error, got {e}"),
+      Ok(_) => panic!("{prompt}: expected a syntax error"),
+    }
+  }
+
+  #[test]
+  fn parses_hints_and_defaults() {
+    let template = parse("Review {{code:long}} in {{lang: rust | go =go}}, {{n:number=3}} times, {{who}} and {{code}}").unwrap();
+    let vars: Vec<_> = template.vars.iter().map(|v| (v.name.as_str(), &v.kind, v.default.as_deref())).collect();
+    assert_eq!(
+      vars,
+      [
+        ("code", &VarKind::Long, None),
+        ("lang", &VarKind::Choice, Some("go")),
+        ("n", &VarKind::Number, Some("3")),
+        ("who", &VarKind::Text, None),
+      ]
+    );
+    assert_eq!(template.vars[1].options, ["rust", "go"]);
+  }
+
+  #[test]
+  fn renders_values_defaults_and_escapes() {
+    let template = parse(r"\{{literal}} {{x}}/{{y=b}}/{{x}}").unwrap();
+    assert_eq!(template.vars.len(), 2);
+    // blank values fall back to the default, unknown names are ignored
+    let out = template.render(&values(&[("x", "a"), ("y", "  "), ("z", "unused")])).unwrap();
+    assert_eq!(out, "{{literal}} a/b/a");
+  }
+
+  #[test]
+  fn reports_every_undefined_and_invalid_value() {
+    let template = parse("{{a}} {{b}} {{n:number}} {{c:x|y}}").unwrap();
+    let Err(TemplateError::Undefined(fields)) = template.render(&values(&[("n", "1"), ("c", "x")])) else {
+      panic!("expected undefined variables");
+    };
+    assert_eq!(fields.iter().map(|f| f.field.as_str()).collect::<Vec<_>>(), ["a", "b"]);
+
+    let Err(TemplateError::Invalid(fields)) = template.render(&values(&[("a", "1"), ("b", "2"), ("n", "ten"), ("c", "z")]))
+    else {
+      panic!("expected invalid values");
+    };
+    assert_eq!(fields.iter().map(|f| f.field.as_str()).collect::<Vec<_>>(), ["n", "c"]);
+  }
+
+  #[test]
+  fn rejects_malformed_placeholders() {
+    assert_eq!(syntax_offset("ok {{never closed"), 3);
+    assert_eq!(syntax_offset("{{}}"), 0);
+    assert_eq!(syntax_offset("a {{1bad}}"), 2);
+    assert_eq!(syntax_offset("{{x:color}}"), 0);
+    assert_eq!(syntax_offset("{{n:number=abc}}"), 0);
+    assert_eq!(syntax_offset("{{x:long}} {{x:number}}"), 11);
+    // a lone `{` or `}}` is plain text
+    assert_eq!(parse("{ x }} y").unwrap().render(&HashMap::new()).unwrap(), "{ x }} y");
+  }
+}
@@ -1,14 +1,17 @@
 use std::{
   env,
   fs,
+  io::Write,
   path::{Path, PathBuf},
+  sync::atomic::{AtomicU64, Ordering},
 };
 
-use tauri::{AppHandle, Emitter, Manager, Theme};
+use tauri::{AppHandle, Emitter, Manager};
 use tauri::process;
 use tauri_plugin_updater::UpdaterExt;
 
-use crate::conf::ChatConfJson;
+use crate::conf::{ChatConfJson, Theme};
+use crate::profile;
 
 use serde::Serialize;
 
@@ -31,29 +34,25 @@ pub fn get_app_info(app: tauri::AppHandle) -> std::result::Result<AppInfo, Strin
 // THEME: apply to all windows, broadcast to React, and persist to chat.conf.json
 // ─────────────────────────────────────────────────────────────────────────────
 #[tauri::command]
-pub fn set_theme_all(app: AppHandle, theme: String) -> std::result::Result<(), String> {
-  let t = theme.to_lowercase();
+pub fn set_theme_all(app: AppHandle, theme: Theme) -> std::result::Result<(), String> {
+  // 1) Persist to disk first, so a theme locked by policy is not applied
+  ChatConfJson::patch(&serde_json::json!({ "theme": theme }), None).map_err(|e| e.to_string())?;
 
-  let native: Option<Theme> = match t.as_str() {
-    "dark" => Some(Theme::Dark),
-    "light" => Some(Theme::Light),
-    _ => None, // "system" -> follow OS
-  };
+  // 2) + 3) Native chrome on every window and the React UIs
+  apply_theme(&app, theme);
+  Ok(())
+}
 
-  // 1) Native chrome on every window (core + config)
+pub fn apply_theme(app: &AppHandle, theme: Theme) {
+  // Native chrome on every window (core + config); "system" follows the OS
   for w in app.webview_windows().values() {
-    let _ = w.set_theme(native);
+    let _ = w.set_theme(theme.native());
   }
 
-  // 2) Tell our React UIs to switch their AntD theme immediately
+  // Tell our React UIs to switch their AntD theme immediately
   for w in app.webview_windows().values() {
-    let _ = w.emit("menu-set-theme", &t);
+    let _ = w.emit("menu-set-theme", theme.as_str());
   }
-
-  // 3) Persist to disk
-  let mut conf = ChatConfJson::load();
-  conf.theme = t;
-  conf.save().map_err(|e| e.to_string())
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -113,19 +112,209 @@ fn user_home() -> PathBuf {
   }
 }
 
-pub fn chat_root() -> PathBuf {
+// ─────────────────────────────────────────────────────────────────────────────
+// Storage locations: XDG Base Directory on unix, a single dotdir on Windows
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Pre-XDG location, only read by `migrate_legacy_home`.
+fn legacy_home() -> PathBuf {
   user_home().join(".chatgpt")
 }
 
+#[cfg(not(windows))]
+fn xdg_home(var: &str, fallback: &str) -> PathBuf {
+  env::var_os(var)
+    .map(PathBuf::from)
+    .filter(|p| p.is_absolute()) // relative values must be ignored per the spec
+    .unwrap_or_else(|| user_home().join(fallback))
+    .join("chatgpt")
+}
+
+pub fn config_home() -> PathBuf {
+  #[cfg(windows)]
+  return legacy_home();
+  #[cfg(not(windows))]
+  return xdg_home("XDG_CONFIG_HOME", ".config");
+}
+
+pub fn data_home() -> PathBuf {
+  #[cfg(windows)]
+  return legacy_home();
+  #[cfg(not(windows))]
+  return xdg_home("XDG_DATA_HOME", ".local/share");
+}
+
+pub fn cache_home() -> PathBuf {
+  #[cfg(windows)]
+  return legacy_home().join("cache");
+  #[cfg(not(windows))]
+  return xdg_home("XDG_CACHE_HOME", ".cache");
+}
+
+pub fn state_home() -> PathBuf {
+  #[cfg(windows)]
+  return legacy_home();
+  #[cfg(not(windows))]
+  return xdg_home("XDG_STATE_HOME", ".local/state");
+}
+
+/// Data directory of the active profile (prompts, notes, downloads).
+pub fn chat_root() -> PathBuf {
+  profile::profile_dir(data_home(), profile::active())
+}
+
+/// Config directory of the active profile (chat.conf.json).
+pub fn chat_config_dir() -> PathBuf {
+  profile::profile_dir(config_home(), profile::active())
+}
+
+/// State directory of the active profile (backups, history).
+pub fn chat_state_dir() -> PathBuf {
+  profile::profile_dir(state_home(), profile::active())
+}
+
+/// Cache directory of the active profile (fetched prompt sources); safe to delete.
+pub fn chat_cache_dir() -> PathBuf {
+  profile::profile_dir(cache_home(), profile::active())
+}
+
+#[tauri::command]
+pub fn get_chat_root() -> PathBuf {
+  chat_root()
+}
+
+/// Reloads a webview so injected scripts pick up a new `chat.model.cmd.json`;
+/// a label without a window (e.g. no tray window) is not an error.
+#[tauri::command]
+pub fn window_reload(app: AppHandle, label: String) -> std::result::Result<(), String> {
+  match app.get_webview_window(&label) {
+    Some(w) => w.eval("window.location.reload()").map_err(|e| e.to_string()),
+    None => Ok(()),
+  }
+}
+
+/// Moves an existing `~/.chatgpt` into the XDG directories once. Entries that
+/// already exist at the destination are left in place rather than overwritten.
+pub fn migrate_legacy_home() -> std::io::Result<()> {
+  let legacy = legacy_home();
+  if cfg!(windows) || !legacy.is_dir() {
+    return Ok(());
+  }
+
+  let profiles = legacy.join("profiles");
+  if let Ok(entries) = fs::read_dir(&profiles) {
+    for entry in entries.flatten() {
+      let name = entry.file_name().to_string_lossy().into_owned();
+      migrate_legacy_dir(&entry.path(), &name)?;
+      let _ = fs::remove_dir(entry.path());
+    }
+    let _ = fs::remove_dir(&profiles);
+  }
+  migrate_legacy_dir(&legacy, profile::DEFAULT_PROFILE)?;
+
+  // only succeeds once everything has been moved out
+  let _ = fs::remove_dir(&legacy);
+  Ok(())
+}
+
+fn migrate_legacy_dir(from: &Path, profile_name: &str) -> std::io::Result<()> {
+  for entry in fs::read_dir(from)?.flatten() {
+    let name = entry.file_name();
+    let dest = match name.to_str() {
+      Some("profiles") => continue,
+      // cache/ becomes the cache dir itself rather than a subfolder of it
+      Some("cache") => profile::profile_dir(cache_home(), profile_name),
+      Some("chat.conf.json") => profile::profile_dir(config_home(), profile_name).join(&name),
+      Some("backups") => profile::profile_dir(state_home(), profile_name).join(&name),
+      _ => profile::profile_dir(data_home(), profile_name).join(&name),
+    };
+    move_entry(&entry.path(), &dest)?;
+  }
+  Ok(())
+}
+
+/// Moves the default profile out of the top of each base dir, where it lived
+/// before every profile got its own `profiles/<name>`, once.
+pub fn migrate_default_profile() -> std::io::Result<()> {
+  let mut bases = vec![config_home(), data_home(), state_home(), cache_home()];
+  bases.sort();
+  bases.dedup(); // config, data and state are one dir on Windows
+  for base in &bases {
+    let Ok(entries) = fs::read_dir(base) else { continue };
+    let dest = profile::profile_dir(base.clone(), profile::DEFAULT_PROFILE);
+    for entry in entries.flatten() {
+      // the other profiles, or the cache dir nested in the data dir on Windows
+      if entry.file_name() == "profiles" || bases.contains(&entry.path()) {
+        continue;
+      }
+      move_entry(&entry.path(), &dest.join(entry.file_name()))?;
+    }
+  }
+  Ok(())
+}
+
+/// Moves a file or tree to `dest` unless something is already there.
+fn move_entry(from: &Path, dest: &Path) -> std::io::Result<()> {
+  if dest.exists() {
+    return Ok(());
+  }
+  if let Some(parent) = dest.parent() {
+    fs::create_dir_all(parent)?;
+  }
+  // rename fails across filesystems (e.g. a separate /home/.local mount)
+  if fs::rename(from, dest).is_err() {
+    copy_tree(from, dest)?;
+    if from.is_dir() {
+      fs::remove_dir_all(from)?;
+    } else {
+      fs::remove_file(from)?;
+    }
+  }
+  Ok(())
+}
+
+fn copy_tree(from: &Path, to: &Path) -> std::io::Result<()> {
+  for entry in walkdir::WalkDir::new(from) {
+    let entry = entry.map_err(std::io::Error::other)?;
+    let dest = to.join(entry.path().strip_prefix(from).unwrap_or(entry.path()));
+    if entry.file_type().is_dir() {
+      fs::create_dir_all(&dest)?;
+    } else {
+      fs::copy(entry.path(), &dest)?;
+    }
+  }
+  Ok(())
+}
+
 pub fn exists<P: AsRef<Path>>(p: P) -> bool {
   p.as_ref().exists()
 }
 
-pub fn create_file<P: AsRef<Path>>(p: P, contents: &str) -> std::io::Result<()> {
-  if let Some(parent) = p.as_ref().parent() {
+/// Makes every temp file name unique, so concurrent writers of one path never
+/// share (and rename) each other's half-written temp file.
+static TMP_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// Writes `contents` to a sibling temp file, syncs it and renames it over `p`,
+/// so a crash or full disk never leaves a truncated file behind.
+pub fn create_file<P: AsRef<Path>, C: AsRef<[u8]>>(p: P, contents: C) -> std::io::Result<()> {
+  let path = p.as_ref();
+  if let Some(parent) = path.parent() {
     fs::create_dir_all(parent)?;
   }
-  fs::write(p, contents)
+
+  let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("file");
+  let seq = TMP_SEQ.fetch_add(1, Ordering::Relaxed);
+  let tmp = path.with_file_name(format!(".{name}.{}.{seq}.tmp", std::process::id()));
+  let written = (|| {
+    let mut file = fs::File::create(&tmp)?;
+    file.write_all(contents.as_ref())?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)
+  })();
+  if written.is_err() {
+    let _ = fs::remove_file(&tmp);
+  }
+  written
 }
 
 pub fn notify_core(app: &AppHandle, title: &str, body: &str) {
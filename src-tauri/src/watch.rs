@@ -0,0 +1,198 @@
+// src-tauri/src/watch.rs  — follow chat.conf.json and re-apply changes live
+
+use std::sync::{
+  atomic::{AtomicBool, Ordering},
+  Mutex,
+};
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+use crate::cmd_index;
+use crate::conf::{Accelerator, ChatConfJson};
+use crate::overrides;
+use crate::scheduler;
+use crate::utils::apply_theme;
+
+// ---------- Event payload ----------
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfFieldChange {
+  pub field: String,
+  pub old: Value,
+  pub new: Value,
+}
+
+/// Payload of the `conf-changed` event broadcast to every window.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfChanged {
+  pub changes: Vec<ConfFieldChange>,
+  pub conf: ChatConfJson,
+  pub revision: String,
+}
+
+// ---------- Managed state ----------
+/// Last config the running app has applied; diffs are taken against it.
+struct AppliedConf(Mutex<ChatConfJson>);
+
+/// Keeps the file watcher alive for the lifetime of the app.
+struct ConfWatcher(#[allow(dead_code)] Mutex<RecommendedWatcher>);
+
+static REBUILDING: AtomicBool = AtomicBool::new(false);
+
+pub fn is_rebuilding() -> bool {
+  REBUILDING.load(Ordering::SeqCst)
+}
+
+// ---------- Startup ----------
+pub fn init(app: &AppHandle, conf: ChatConfJson) {
+  apply_theme(app, conf.theme);
+  apply_stay_on_top(app, conf.stay_on_top);
+  apply_global_shortcut(app, &conf.global_shortcut);
+  app.manage(AppliedConf(Mutex::new(conf)));
+
+  match watch_conf(app) {
+    Ok(watcher) => {
+      app.manage(ConfWatcher(Mutex::new(watcher)));
+    }
+    Err(e) => eprintln!("Failed to watch chat.conf.json: {e}"),
+  }
+}
+
+fn watch_conf(app: &AppHandle) -> notify::Result<RecommendedWatcher> {
+  let path = ChatConfJson::conf_path();
+  // Watch the directory: atomic saves replace the file, which drops a per-file watch.
+  let dir = path.parent().map(|p| p.to_path_buf()).unwrap_or_default();
+  std::fs::create_dir_all(&dir)?;
+
+  let handle = app.clone();
+  let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+    let Ok(event) = res else { return };
+    if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+      return;
+    }
+    if event.paths.iter().any(|p| p.file_name() == path.file_name()) {
+      reload(&handle);
+    }
+  })?;
+  watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+  Ok(watcher)
+}
+
+// ---------- Reload + diff ----------
+fn reload(app: &AppHandle) {
+  // A half-written file from an external editor is ignored until the next
+  // event; `try_load` would restore a backup over it, so that stays at startup.
+  let Ok((conf, _)) = ChatConfJson::read(&ChatConfJson::conf_path()) else { return };
+  // env / --set overrides keep winning over whatever the file now says
+  let conf = overrides::apply(conf, true).conf;
+
+  let changes = {
+    let state = app.state::<AppliedConf>();
+    let mut applied = state.0.lock().unwrap();
+    let changes = diff(&applied, &conf);
+    if changes.is_empty() {
+      return;
+    }
+    *applied = conf.clone();
+    changes
+  };
+
+  for change in &changes {
+    apply_field(app, &conf, change);
+  }
+  let revision = ChatConfJson::revision();
+  let _ = app.emit("conf-changed", ConfChanged { changes, conf, revision });
+}
+
+fn diff(old: &ChatConfJson, new: &ChatConfJson) -> Vec<ConfFieldChange> {
+  let (Ok(Value::Object(old)), Ok(Value::Object(new))) = (serde_json::to_value(old), serde_json::to_value(new)) else {
+    return vec![];
+  };
+
+  new
+    .into_iter()
+    .filter_map(|(field, new)| {
+      let old = old.get(&field).cloned().unwrap_or(Value::Null);
+      (old != new).then_some(ConfFieldChange { field, old, new })
+    })
+    .collect()
+}
+
+// ---------- Re-apply ----------
+fn apply_field(app: &AppHandle, conf: &ChatConfJson, change: &ConfFieldChange) {
+  match change.field.as_str() {
+    "theme" => apply_theme(app, conf.theme),
+    "stay_on_top" => apply_stay_on_top(app, conf.stay_on_top),
+    "global_shortcut" => apply_global_shortcut(app, &conf.global_shortcut),
+    "origin" => {
+      if let Some(core) = app.get_webview_window("core") {
+        let _ = core.navigate(conf.origin.url().clone());
+      }
+    }
+    "sync_interval" => scheduler::reschedule(),
+    "cmd_precedence" => {
+      if let Err(e) = cmd_index::rebuild() {
+        eprintln!("Failed to rebuild chat.model.cmd.json: {e}");
+      }
+    }
+    // The webview has no runtime user-agent setter, so the window is rebuilt.
+    "ua_window" => rebuild_window(app, "core", conf.clone()),
+    // an unopened tray window picks the new user agent up when it is built
+    "ua_tray" if app.get_webview_window("tray").is_some() => rebuild_window(app, "tray", conf.clone()),
+    _ => {}
+  }
+}
+
+fn apply_stay_on_top(app: &AppHandle, on_top: bool) {
+  for w in app.webview_windows().values() {
+    let _ = w.set_always_on_top(on_top);
+  }
+}
+
+/// Replaces whatever shortcut was registered; an empty accelerator disables it.
+fn apply_global_shortcut(app: &AppHandle, accelerator: &Accelerator) {
+  let shortcuts = app.global_shortcut();
+  let _ = shortcuts.unregister_all();
+  if accelerator.as_str().is_empty() {
+    return;
+  }
+
+  let registered = shortcuts.on_shortcut(accelerator.as_str(), |app, _, event| {
+    if event.state() != ShortcutState::Pressed {
+      return;
+    }
+    if let Some(core) = app.get_webview_window("core") {
+      if core.is_visible().unwrap_or(false) && core.is_focused().unwrap_or(false) {
+        let _ = core.hide();
+      } else {
+        let _ = core.show();
+        let _ = core.set_focus();
+      }
+    }
+  });
+  if let Err(e) = registered {
+    let _ = app.emit("notice", format!("Global shortcut \"{accelerator}\" is not available: {e}"));
+  }
+}
+
+/// Replaces the `core` or `tray` window with one built from `conf`.
+fn rebuild_window(app: &AppHandle, label: &'static str, conf: ChatConfJson) {
+  let handle = app.clone();
+  let _ = app.run_on_main_thread(move || {
+    REBUILDING.store(true, Ordering::SeqCst);
+    if let Some(win) = handle.get_webview_window(label) {
+      let _ = win.destroy();
+    }
+    let built = match label {
+      "tray" => crate::build_tray_window(&handle, &conf),
+      _ => crate::build_core_window(&handle, &conf),
+    };
+    if let Err(e) = built {
+      let _ = handle.emit("notice", format!("Failed to apply user agent: {e}"));
+    }
+    REBUILDING.store(false, Ordering::SeqCst);
+  });
+}
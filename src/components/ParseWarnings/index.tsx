@@ -0,0 +1,19 @@
+import { Modal } from 'antd';
+
+export type ParseWarning = { line: number; reason: string };
+
+// rows the prompt parser skipped or repaired, so the source file can be fixed
+export const showParseWarnings = (title: string, warnings: ParseWarning[] = []) => {
+  if (warnings.length <= 0) return;
+  Modal.warning({
+    title,
+    width: 560,
+    content: (
+      <div style={{ maxHeight: 320, overflow: 'auto' }}>
+        {warnings.map((i, idx) => (
+          <div key={idx}><strong>Line {i.line}</strong>: {i.reason}</div>
+        ))}
+      </div>
+    ),
+  });
+};
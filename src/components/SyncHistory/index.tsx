@@ -0,0 +1,122 @@
+import { useState } from 'react';
+import { Button, Drawer, Modal, Popconfirm, Table, Tag, message } from 'antd';
+import { invoke } from '@tauri-apps/api/core';
+
+import { fmtDate } from '@/utils';
+
+export type HistoryEntry = {
+  id: string;
+  source_id: string;
+  source_name: string;
+  timestamp: number;
+  rollback_of?: string | null;
+  added: number;
+  removed: number;
+  modified: number;
+};
+
+type DiffSpan = { tag: 'equal' | 'insert' | 'delete'; text: string };
+type DiffChange = { act: string; kind: 'added' | 'removed' | 'modified'; old?: string; new?: string; spans?: DiffSpan[] };
+type SyncDiff = { from: HistoryEntry; to: HistoryEntry; changes: DiffChange[] };
+
+const KIND_COLOR = { added: 'green', removed: 'red', modified: 'gold' };
+const SPAN_STYLE = {
+  equal: {},
+  insert: { background: 'rgba(34,197,94,.25)' },
+  delete: { background: 'rgba(239,68,68,.25)', textDecoration: 'line-through' },
+};
+
+const showDiff = (diff: SyncDiff) => {
+  Modal.info({
+    title: `${fmtDate(diff.from.timestamp)} → ${fmtDate(diff.to.timestamp)}`,
+    width: 720,
+    content: (
+      <div style={{ maxHeight: 420, overflow: 'auto' }}>
+        {diff.changes.length <= 0 && <div>No differences.</div>}
+        {diff.changes.map((c) => (
+          <div key={`${c.kind}-${c.act}`} style={{ marginBottom: 12 }}>
+            <Tag color={KIND_COLOR[c.kind]}>{c.kind}</Tag><strong>{c.act}</strong>
+            <div style={{ whiteSpace: 'pre-wrap', marginTop: 4 }}>
+              {c.kind === 'modified'
+                ? c.spans?.map((s, idx) => <span key={idx} style={SPAN_STYLE[s.tag]}>{s.text}</span>)
+                : <span style={SPAN_STYLE[c.kind === 'added' ? 'insert' : 'delete']}>{c.new ?? c.old}</span>}
+            </div>
+          </div>
+        ))}
+      </div>
+    ),
+  });
+};
+
+// past syncs of one prompt source, each diffable against the one before it and restorable
+export default function SyncHistory({ sourceId, onRollback }: { sourceId: string; onRollback?: () => void }) {
+  const [open, setOpen] = useState(false);
+  const [entries, setEntries] = useState<HistoryEntry[]>([]);
+
+  const load = async () => {
+    setEntries(await invoke<HistoryEntry[]>('list_sync_history', { sourceId }));
+  };
+
+  const handleOpen = async () => {
+    setOpen(true);
+    await load();
+  };
+
+  const handleDiff = async (idx: number) => {
+    try {
+      showDiff(await invoke<SyncDiff>('diff_sync', { a: entries[idx + 1].id, b: entries[idx].id }));
+    } catch (e) {
+      message.error(String(e));
+    }
+  };
+
+  const handleRollback = async (entry: HistoryEntry) => {
+    try {
+      await invoke('rollback_sync', { id: entry.id });
+      message.success(`Restored the prompts of ${fmtDate(entry.timestamp)}`);
+      await load();
+      onRollback?.();
+    } catch (e) {
+      message.error(String(e));
+    }
+  };
+
+  const columns = [
+    { title: 'Synced', dataIndex: 'timestamp', key: 'timestamp', render: (v: number) => fmtDate(v) },
+    {
+      title: 'Changes',
+      key: 'changes',
+      render: (_: any, row: HistoryEntry) => (
+        <>
+          {row.rollback_of && <Tag>rollback</Tag>}
+          <Tag color="green">+{row.added}</Tag>
+          <Tag color="red">-{row.removed}</Tag>
+          <Tag color="gold">~{row.modified}</Tag>
+        </>
+      ),
+    },
+    {
+      title: 'Action',
+      key: 'action',
+      render: (_: any, row: HistoryEntry, idx: number) => (
+        <>
+          {idx < entries.length - 1 && <a onClick={() => handleDiff(idx)} style={{ marginRight: 10 }}>Diff</a>}
+          {idx > 0 && (
+            <Popconfirm title="Replace the current prompts with this snapshot?" onConfirm={() => handleRollback(row)} okText="Yes" cancelText="No">
+              <a>Rollback</a>
+            </Popconfirm>
+          )}
+        </>
+      ),
+    },
+  ];
+
+  return (
+    <>
+      <Button onClick={handleOpen}>History</Button>
+      <Drawer title="Sync History" width={560} open={open} onClose={() => setOpen(false)}>
+        <Table rowKey="id" size="small" columns={columns} dataSource={entries} pagination={false} />
+      </Drawer>
+    </>
+  );
+}
@@ -2,7 +2,7 @@ import { useState, useEffect } from 'react';
 import { clone } from 'lodash';
 import { invoke } from '@tauri-apps/api/core';
 
-import { CHAT_MODEL_JSON, CHAT_MODEL_CMD_JSON, readJSON, writeJSON } from '@/utils';
+import { CHAT_MODEL_JSON, readJSON, writeJSON } from '@/utils';
 import useInit from '@/hooks/useInit';
 
 export default function useChatModel(key: string, file = CHAT_MODEL_JSON) {
@@ -43,9 +43,8 @@ export function useCacheModel(file = '') {
   }
 
   const modelCacheCmd = async () => {
-    // Generate the `chat.model.cmd.json` file and refresh the page for the slash command to take effect.
-    const list = await invoke('cmd_list');
-    await writeJSON(CHAT_MODEL_CMD_JSON, { name: 'ChatGPT CMD', last_updated: Date.now(), data: list });
+    // Rebuild the `chat.model.cmd.json` file and refresh the page for the slash command to take effect.
+    await invoke('cmd_list');
     await invoke('window_reload', { label: 'core' });
     await invoke('window_reload', { label: 'tray' });
   };
@@ -501,3 +501,16 @@ if (_replace) {
     try { console.log('[notice]', ev?.payload); } catch {}
   });
 })();
+// Report connectivity to the background prompt sync, which holds runs while offline.
+(function () {
+  const g = window as any;
+  if (g.__online_bound) return;
+  const invoke = g.__TAURI__?.core?.invoke;
+  if (typeof invoke !== 'function') return;
+  g.__online_bound = true;
+
+  const report = () => invoke('set_online', { online: navigator.onLine }).catch(() => {});
+  window.addEventListener('online', report);
+  window.addEventListener('offline', report);
+  report();
+})();
@@ -35,14 +35,6 @@ useInit(async () => {
   }
 });
 
-  // menubar "toggle" event (optional)
-  useEffect(() => {
-    const unlisten = listen('menu-toggle-dark', () => {
-      setAppInfo(prev => ({ ...prev, appTheme: prev.appTheme === 'dark' ? 'light' : 'dark' }));
-    });
-    return () => { unlisten.then(u => u()); };
-  }, []);
-
   // deterministic theme-set event
   useEffect(() => {
     const unlisten = listen<string>('menu-set-theme', (e) => {
@@ -3,7 +3,8 @@ import { readTextFile, writeTextFile } from "@tauri-apps/plugin-fs";
 import { open, save, message } from "@tauri-apps/plugin-dialog";
 import { relaunch, exit } from "@tauri-apps/plugin-process";
 import { platform, type, version } from "@tauri-apps/plugin-os";
-import { homeDir, join, dirname } from '@tauri-apps/api/path';
+import { join, dirname } from '@tauri-apps/api/path';
+import { invoke } from '@tauri-apps/api/core';
 import dayjs from 'dayjs';
 
 export const CHAT_MODEL_JSON = 'chat.model.json';
@@ -18,8 +19,9 @@ export const DISABLE_AUTO_COMPLETE = {
   spellCheck: false
 };
 
-export const chatRoot = async () => {
-  return join(await homeDir(), '.chatgpt')
+// data directory of the profile this window belongs to (see `--profile`)
+export const chatRoot = async (): Promise<string> => {
+  return invoke<string>('get_chat_root');
 }
 
 export const chatModelPath = async (): Promise<string> => {
@@ -1,16 +1,16 @@
 import { useEffect, useState } from 'react';
-import { Form, Radio, Switch, Input, Button, Space, message, Tooltip } from 'antd';
+import { Form, Radio, Switch, Input, Button, Space, message, Tooltip, Alert, Select, Divider, Checkbox, Modal, InputNumber } from 'antd';
 import { QuestionCircleOutlined } from '@ant-design/icons';
 import { invoke } from '@tauri-apps/api/core';
 import * as shell from '@tauri-apps/plugin-shell';
-import * as path from '@tauri-apps/api/path';
-import { ask } from '@tauri-apps/plugin-dialog';
+import { ask, open, save } from '@tauri-apps/plugin-dialog';
 import { relaunch } from '@tauri-apps/plugin-process';
-import { clone, omit, isEqual } from 'lodash';
-import { emit } from '@tauri-apps/api/event';
+import { clone, isEqual } from 'lodash';
+import { emit, listen } from '@tauri-apps/api/event';
+import dayjs from 'dayjs';
 
 import useInit from '@/hooks/useInit';
-import { DISABLE_AUTO_COMPLETE, chatRoot } from '@/utils';
+import { DISABLE_AUTO_COMPLETE, GITHUB_PROMPTS_CSV_URL, fmtDate } from '@/utils';
 
 const AutoUpdateLabel = () => (
   <span>
@@ -18,9 +18,7 @@ const AutoUpdateLabel = () => (
     <Tooltip
       title={
         <div>
-          <div>Auto Update Policy</div>
-          <span><strong>Prompt</strong>: prompt to install</span><br />
-          <span><strong>Silent</strong>: install silently</span><br />
+          <div>Install new versions automatically</div>
         </div>
       }
     >
@@ -35,6 +33,16 @@ const OriginLabel = ({ url }: { url: string }) => (
   </span>
 );
 
+type ConfError = { kind: string; message: string; path: string; fields: { field: string; message: string }[] };
+type ConfBackup = { name: string; created_at_ms: number; size: number };
+type Profile = { name: string; active: boolean; root: string };
+type ImportMode = 'skip' | 'merge' | 'replace';
+type BundleEntry = { name: string; kind: 'file' | 'tree'; size: number; local_exists: boolean; conflicts: number };
+type BundlePreview = { app_version: string; profile: string; created_at_ms: number; entries: BundleEntry[] };
+type ArchiveSummary = { added: number; updated: number; unchanged: number; skipped: number; failed: number; errors: string[]; attachments: number; missing_attachments: number };
+type FieldSource = { source: 'default' | 'file' | 'env' | 'cli' | 'policy'; origin?: string };
+type EffectiveConf = { conf: any; sources: Record<string, FieldSource>; rejected: string[] };
+
 const GlobalShortcutLabel = () => (
   <div>
     Global Shortcut{' '}
@@ -56,41 +64,209 @@ export default function General() {
   const [form] = Form.useForm();
   const [jsonPath, setJsonPath] = useState('');
   const [chatConf, setChatConf] = useState<any>(null);
+  const [confError, setConfError] = useState<ConfError | null>(null);
+  const [backups, setBackups] = useState<ConfBackup[]>([]);
+  const [effective, setEffective] = useState<EffectiveConf | null>(null);
+
+  const [profiles, setProfiles] = useState<Profile[]>([]);
+  const [newProfile, setNewProfile] = useState('');
+
+  const [includeTrees, setIncludeTrees] = useState(true);
+  const [bundle, setBundle] = useState<{ path: string; preview: BundlePreview } | null>(null);
+  const [importModes, setImportModes] = useState<Record<string, ImportMode>>({});
+
+  const refreshBackups = async () => setBackups(await invoke<ConfBackup[]>('list_conf_backups'));
+  const refreshProfiles = async () => setProfiles(await invoke<Profile[]>('list_profiles'));
+  const refreshEffective = async () => setEffective(await invoke<EffectiveConf>('get_effective_conf'));
+
+  // fields forced by CHATGPT_* env vars or --set flags for this session
+  const overridden = Object.entries(effective?.sources ?? {}).filter(([, s]) => s.source === 'env' || s.source === 'cli');
+  // fields pinned by the administrator's policy.json are read-only here
+  const locked = (field: string) => effective?.sources?.[field]?.source === 'policy';
+  const lockedExtra = (field: string) => locked(field) ? `Locked by ${effective?.sources?.[field]?.origin}` : undefined;
 
   useInit(async () => {
-    setJsonPath(await path.join(await chatRoot(), 'chat.conf.json'));
-    const conf = await invoke<any>('get_chat_conf');
-    // normalize theme to lowercase for the radio group
-    conf.theme = String(conf?.theme ?? 'system').toLowerCase();
-    setChatConf(conf);
+    refreshBackups();
+    refreshProfiles();
+    refreshEffective();
+    setJsonPath(await invoke<string>('get_chat_conf_path'));
+    try {
+      const conf = await invoke<any>('get_chat_conf');
+      // normalize theme to lowercase for the radio group
+      conf.theme = String(conf?.theme ?? 'system').toLowerCase();
+      setChatConf(conf);
+    } catch (e) {
+      // the file is left untouched; saving or resetting here replaces it
+      setConfError(e as ConfError);
+    }
   });
 
   useEffect(() => {
     if (chatConf) form.setFieldsValue(clone(chatConf));
   }, [chatConf, form]);
 
+  // chat.conf.json was changed (here, in another window or by an external editor)
+  useEffect(() => {
+    const unlisten = listen<{ conf: any; revision: string }>('conf-changed', (e) => {
+      const conf = { ...e.payload.conf, revision: e.payload.revision };
+      conf.theme = String(conf?.theme ?? 'system').toLowerCase();
+      setChatConf(conf);
+      setConfError(null);
+      refreshEffective();
+    });
+    return () => { unlisten.then(u => u()); };
+  }, []);
+
   const onCancel = () => {
     form.setFieldsValue(chatConf);
   };
 
   const onReset = async () => {
-    const conf = await invoke<any>('reset_chat_conf');
+    let conf: any;
+    try {
+      conf = await invoke<any>('reset_chat_conf');
+    } catch (e) {
+      const err = e as ConfError;
+      if (err?.fields?.length) {
+        form.setFields(err.fields.map(i => ({ name: i.field, errors: [i.message] })));
+      }
+      message.error(err?.message || String(e));
+      return;
+    }
     conf.theme = String(conf?.theme ?? 'system').toLowerCase();
     setChatConf(conf);
+    setConfError(null);
     const isOk = await ask('Configuration reset successfully, whether to restart?', { title: 'ChatGPT Preferences' });
     if (isOk) { relaunch(); return; }
     message.success('Configuration reset successfully');
   };
 
+  const onRestore = async (name: string) => {
+    const isConfirm = await ask(`Replace the current configuration with the backup from ${fmtDate(backups.find(i => i.name === name)?.created_at_ms)}?`, { title: 'ChatGPT Preferences' });
+    if (!isConfirm) return;
+    try {
+      const conf = await invoke<any>('restore_conf_backup', { name });
+      conf.theme = String(conf?.theme ?? 'system').toLowerCase();
+      setChatConf(conf);
+      setConfError(null);
+      await refreshBackups();
+      message.success('Configuration restored from backup');
+    } catch (e) {
+      message.error((e as ConfError)?.message || 'Failed to restore backup');
+    }
+  };
+
+  const onOpenProfile = async (name: string) => {
+    try {
+      await invoke('open_profile', { name });
+    } catch (e) {
+      message.error(String(e));
+    }
+  };
+
+  const onCreateProfile = async () => {
+    try {
+      const profile = await invoke<Profile>('create_profile', { name: newProfile.trim() });
+      setNewProfile('');
+      await refreshProfiles();
+      await onOpenProfile(profile.name);
+    } catch (e) {
+      message.error(String(e));
+    }
+  };
+
+  const reloadConf = async () => {
+    const conf = await invoke<any>('get_chat_conf');
+    conf.theme = String(conf?.theme ?? 'system').toLowerCase();
+    setChatConf(conf);
+  };
+
+  const onExportBundle = async () => {
+    const path = await save({
+      defaultPath: `chatgpt-${dayjs().format('YYYYMMDD-HHmmss')}.zip`,
+      filters: [{ name: 'ChatGPT bundle', extensions: ['zip'] }],
+    });
+    if (!path) return;
+    try {
+      const summary = await invoke<{ files: number }>('export_bundle', { path, includeTrees });
+      message.success(`Exported ${summary.files} files`);
+    } catch (e) {
+      message.error(String(e));
+    }
+  };
+
+  const onPickBundle = async () => {
+    const path = await open({ multiple: false, filters: [{ name: 'ChatGPT bundle', extensions: ['zip'] }] });
+    if (!path || Array.isArray(path)) return;
+    try {
+      const preview = await invoke<BundlePreview>('preview_bundle', { path });
+      // merge into anything that already exists, otherwise just take the bundle's copy
+      setImportModes(Object.fromEntries(preview.entries.map(i => [i.name, i.local_exists ? 'merge' : 'replace'])));
+      setBundle({ path, preview });
+    } catch (e) {
+      message.error(String(e));
+    }
+  };
+
+  const onImportBundle = async () => {
+    if (!bundle) return;
+    try {
+      const applied = await invoke<string[]>('import_bundle', { path: bundle.path, modes: importModes });
+      setBundle(null);
+      refreshBackups();
+      const isOk = await ask(`Imported ${applied.length} entries, whether to restart?`, { title: 'ChatGPT Preferences' });
+      if (isOk) relaunch();
+    } catch (e) {
+      message.error(String(e));
+    }
+  };
+
+  const onImportOpenAIExport = async () => {
+    const path = await open({ multiple: false, filters: [{ name: 'ChatGPT data export', extensions: ['zip'] }] });
+    if (!path || Array.isArray(path)) return;
+    const hide = message.loading('Importing conversations...', 0);
+    try {
+      const s = await invoke<ArchiveSummary>('import_openai_export', { path });
+      const missing = s.missing_attachments ? `, ${s.missing_attachments} attachments not in the export` : '';
+      message.success(`${s.added} added, ${s.updated} updated, ${s.unchanged} unchanged, ${s.attachments} attachments copied${missing}`);
+      if (s.failed) {
+        message.warning(`${s.failed} conversations could not be imported and will be retried next time: ${s.errors.join('; ')}`, 8);
+      }
+    } catch (e) {
+      message.error(String(e));
+    } finally {
+      hide();
+    }
+  };
+
   const onFinish = async (values: any) => {
-    const merged = { ...(chatConf ?? {}), ...values, theme: String(values.theme ?? 'system').toLowerCase() };
-    if (!isEqual(omit(chatConf, ['default_origin']), merged)) {
-      await invoke('set_chat_conf', { conf: merged });   // <-- save to disk
-      try { await emit('menu-set-theme', merged.theme); } catch {}
-      const isOk = await ask('Configuration saved successfully, whether to restart?', { title: 'ChatGPT Preferences' });
-      if (isOk) { relaunch(); return; }
+    const next = { ...values, theme: String(values.theme ?? 'system').toLowerCase() };
+    // only send what was edited, so concurrent edits to other fields survive
+    const patch = Object.fromEntries(
+      Object.entries(next).filter(([k, v]) => !isEqual(chatConf?.[k], v)),
+    );
+    if (Object.keys(patch).length) {
+      let conf: any;
+      try {
+        // <-- save to disk, applied live via `conf-changed`
+        conf = await invoke<any>('patch_chat_conf', { patch, revision: chatConf?.revision ?? null });
+      } catch (e) {
+        // invalid values are rejected before anything is written
+        const err = e as ConfError;
+        if (err?.kind === 'conflict') {
+          message.warning('The configuration was changed elsewhere and has been reloaded, please review and save again');
+          await reloadConf();
+        } else if (err?.fields?.length) {
+          form.setFields(err.fields.map(i => ({ name: i.field, errors: [i.message] })));
+        } else {
+          message.error(err?.message || String(e));
+        }
+        return;
+      }
       message.success('Configuration saved successfully');
-      setChatConf(merged);
+      conf.theme = String(conf?.theme ?? 'system').toLowerCase();
+      setChatConf(conf);
+      refreshBackups();
     }
   };
 
@@ -102,6 +278,34 @@ export default function General() {
         </div>
       </div>
 
+      {confError && (
+        <Alert
+          type="error"
+          showIcon
+          style={{ maxWidth: 500, marginBottom: 20 }}
+          message="Configuration could not be loaded"
+          description={confError.message}
+        />
+      )}
+
+      {(overridden.length > 0 || !!effective?.rejected.length) && (
+        <Alert
+          type="info"
+          showIcon
+          style={{ maxWidth: 500, marginBottom: 20 }}
+          message="Some values are overridden for this session"
+          description={
+            <div>
+              {overridden.map(([field, s]) => (
+                <div key={field}><code>{field}</code> = {JSON.stringify(effective?.conf?.[field])} from <code>{s.origin}</code></div>
+              ))}
+              {effective?.rejected.map(i => <div key={i}>Ignored invalid <code>{i}</code></div>)}
+              <div style={{ marginTop: 8 }}>Values saved here are written to chat.conf.json and apply once the override is removed.</div>
+            </div>
+          }
+        />
+      )}
+
       <Form
         form={form}
         style={{ maxWidth: 500 }}
@@ -109,12 +313,13 @@ export default function General() {
         labelCol={{ span: 8 }}
         wrapperCol={{ span: 15, offset: 1 }}
       >
-        <Form.Item label="Stay On Top" name="stay_on_top" valuePropName="checked">
-          <Switch />
+        <Form.Item label="Stay On Top" name="stay_on_top" valuePropName="checked" extra={lockedExtra('stay_on_top')}>
+          <Switch disabled={locked('stay_on_top')} />
         </Form.Item>
 
-        <Form.Item label="Theme" name="theme">
+        <Form.Item label="Theme" name="theme" extra={lockedExtra('theme')}>
           <Radio.Group
+            disabled={locked('theme')}
             onChange={async (e) => {
               const v = String(e.target.value || 'system').toLowerCase();
               form.setFieldValue('theme', v);
@@ -134,27 +339,44 @@ export default function General() {
           </Radio.Group>
         </Form.Item>
 
-        <Form.Item label={<AutoUpdateLabel />} name="auto_update">
-          <Radio.Group>
-            <Radio value="prompt">Prompt</Radio>
-            <Radio value="silent">Silent</Radio>
-          </Radio.Group>
+        <Form.Item label={<AutoUpdateLabel />} name="auto_update" valuePropName="checked" extra={lockedExtra('auto_update')}>
+          <Switch disabled={locked('auto_update')} />
+        </Form.Item>
+
+        <Form.Item label={<GlobalShortcutLabel />} name="global_shortcut" extra={lockedExtra('global_shortcut')}>
+          <Input disabled={locked('global_shortcut')} placeholder="CmdOrCtrl+Shift+O" {...DISABLE_AUTO_COMPLETE} />
+        </Form.Item>
+
+        <Form.Item label={<OriginLabel url={chatConf?.default_origin} />} name="origin" extra={lockedExtra('origin')}>
+          <Input disabled={locked('origin')} placeholder="https://chat.openai.com" {...DISABLE_AUTO_COMPLETE} />
         </Form.Item>
 
-        <Form.Item label={<GlobalShortcutLabel />} name="global_shortcut">
-          <Input placeholder="CmdOrCtrl+Shift+O" {...DISABLE_AUTO_COMPLETE} />
+        <Form.Item label="Prompts CSV URL" name="prompts_url" extra={lockedExtra('prompts_url')}>
+          <Input disabled={locked('prompts_url')} placeholder={GITHUB_PROMPTS_CSV_URL} {...DISABLE_AUTO_COMPLETE} />
         </Form.Item>
 
-        <Form.Item label={<OriginLabel url={chatConf?.default_origin} />} name="origin">
-          <Input placeholder="https://chat.openai.com" {...DISABLE_AUTO_COMPLETE} />
+        <Form.Item label="Background Sync" name="sync_interval" extra={lockedExtra('sync_interval') ?? 'Minutes between syncs of the remote prompt sources, 0 to turn off'}>
+          <InputNumber disabled={locked('sync_interval')} min={0} step={5} addonAfter="min" />
         </Form.Item>
 
-        <Form.Item label="User Agent (Window)" name="ua_window">
-          <Input.TextArea autoSize={{ minRows: 4, maxRows: 4 }} {...DISABLE_AUTO_COMPLETE} placeholder="Mozilla/5.0 ..." />
+        <Form.Item label="Command Precedence" name="cmd_precedence" extra={lockedExtra('cmd_precedence') ?? 'Wins when two sources define the same /command, highest first'}>
+          <Select
+            mode="multiple"
+            disabled={locked('cmd_precedence')}
+            options={[
+              { value: 'user', label: 'User Custom' },
+              { value: 'custom', label: 'Sync Custom' },
+              { value: 'awesome', label: 'awesome-chatgpt-prompts' },
+            ]}
+          />
         </Form.Item>
 
-        <Form.Item label="User Agent (SystemTray)" name="ua_tray">
-          <Input.TextArea autoSize={{ minRows: 4, maxRows: 4 }} {...DISABLE_AUTO_COMPLETE} placeholder="Mozilla/5.0 ..." />
+        <Form.Item label="User Agent (Window)" name="ua_window" extra={lockedExtra('ua_window')}>
+          <Input.TextArea disabled={locked('ua_window')} autoSize={{ minRows: 4, maxRows: 4 }} {...DISABLE_AUTO_COMPLETE} placeholder="Mozilla/5.0 ..." />
+        </Form.Item>
+
+        <Form.Item label="User Agent (SystemTray)" name="ua_tray" extra={lockedExtra('ua_tray')}>
+          <Input.TextArea disabled={locked('ua_tray')} autoSize={{ minRows: 4, maxRows: 4 }} {...DISABLE_AUTO_COMPLETE} placeholder="Mozilla/5.0 ..." />
         </Form.Item>
 
         <Form.Item>
@@ -162,9 +384,75 @@ export default function General() {
             <Button onClick={onCancel}>Cancel</Button>
             <Button type="primary" htmlType="submit">Submit</Button>
             <Button type="dashed" onClick={onReset}>Reset to defaults</Button>
+            <Select
+              value={null}
+              placeholder="Restore backup"
+              style={{ width: 180 }}
+              disabled={backups.length === 0}
+              onChange={onRestore}
+              options={backups.map(i => ({ value: i.name, label: fmtDate(i.created_at_ms) }))}
+            />
           </Space>
         </Form.Item>
       </Form>
+
+      <Divider orientation="left">Profiles</Divider>
+      <Space size={20}>
+        <Select
+          value={profiles.find(i => i.active)?.name}
+          style={{ width: 180 }}
+          onChange={onOpenProfile}
+          options={profiles.map(i => ({ value: i.name, label: i.name }))}
+        />
+        <Input
+          value={newProfile}
+          placeholder="work"
+          style={{ width: 180 }}
+          onChange={(e) => setNewProfile(e.target.value)}
+          {...DISABLE_AUTO_COMPLETE}
+        />
+        <Button disabled={!newProfile.trim()} onClick={onCreateProfile}>Create and open</Button>
+      </Space>
+
+      <Divider orientation="left">Export / Import</Divider>
+      <Space size={20}>
+        <Checkbox checked={includeTrees} onChange={(e) => setIncludeTrees(e.target.checked)}>Include notes/, download/ and archive/</Checkbox>
+        <Button onClick={onExportBundle}>Export bundle</Button>
+        <Button onClick={onPickBundle}>Import bundle</Button>
+        <Button onClick={onImportOpenAIExport}>Import ChatGPT data export</Button>
+      </Space>
+
+      <Modal
+        open={!!bundle}
+        title="Import bundle"
+        okText="Import"
+        width={600}
+        onOk={onImportBundle}
+        onCancel={() => setBundle(null)}
+      >
+        <p>
+          Exported from profile <code>{bundle?.preview.profile}</code> (v{bundle?.preview.app_version})
+          on {fmtDate(bundle?.preview.created_at_ms)}
+        </p>
+        {bundle?.preview.entries.map(i => (
+          <div key={i.name} style={{ display: 'flex', justifyContent: 'space-between', marginBottom: 8 }}>
+            <span>
+              <code>{i.name}</code>{' '}
+              {i.kind === 'tree' ? `${i.size} files` : `${i.size} bytes`}
+              {i.conflicts > 0 && `, replaces ${i.conflicts} local ${i.kind === 'tree' ? 'files' : 'copy'}`}
+            </span>
+            <Radio.Group
+              size="small"
+              value={importModes[i.name]}
+              onChange={(e) => setImportModes({ ...importModes, [i.name]: e.target.value })}
+            >
+              <Radio.Button value="skip">Skip</Radio.Button>
+              <Radio.Button value="merge" disabled={!i.local_exists}>Merge</Radio.Button>
+              <Radio.Button value="replace">Replace</Radio.Button>
+            </Radio.Group>
+          </div>
+        ))}
+      </Modal>
     </>
   );
 }
\ No newline at end of file
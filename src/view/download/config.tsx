@@ -11,6 +11,7 @@ import { fmtDate, chatRoot } from '@/utils';
 const colorMap: any = {
   pdf: 'blue',
   png: 'orange',
+  html: 'green',
 }
 
 export const downloadColumns = () => [
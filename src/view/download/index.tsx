@@ -17,6 +17,7 @@ function renderFile(buff: Uint8Array, type: string) {
     {
       pdf: 'application/pdf',
       png: 'image/png',
+      html: 'text/html',
     }[type] || 'application/octet-stream';
 
   // FIX: convert Uint8Array<ArrayBufferLike> -> ArrayBuffer
@@ -161,8 +162,10 @@ export default function Download() {
         rowSelection={rowSelection}
         pagination={TABLE_PAGINATION}
       />
-      <Modal open={isVisible} title={<div>{opInfo?.opRecord?.name || ''}</div>} onCancel={handleCancel} footer={false} destroyOnClose>
-        <img style={{ maxWidth: '100%' }} src={source} />
+      <Modal open={isVisible} title={<div>{opInfo?.opRecord?.name || ''}</div>} onCancel={handleCancel} footer={false} width={['html', 'pdf'].includes(opInfo?.opRecord?.ext) ? 900 : undefined} destroyOnClose>
+        {opInfo?.opRecord?.ext === 'html' && <iframe sandbox="allow-scripts" style={{ width: '100%', height: '70vh', border: 0 }} src={source} />}
+        {opInfo?.opRecord?.ext === 'pdf' && <iframe style={{ width: '100%', height: '70vh', border: 0 }} src={source} />}
+        {opInfo?.opRecord?.ext === 'png' && <img style={{ maxWidth: '100%' }} src={source} />}
       </Modal>
     </div>
   );
@@ -18,6 +18,18 @@ const initFormValue = {
   prompt: '',
 };
 
+// false when `path` resolves to `root` itself or one of its parents
+const isWatchable = (root: string, path: string) => {
+  const absolute = /^([\\/]|[a-zA-Z]:)/.test(path.trim());
+  const parts: string[] = [];
+  for (const part of `${absolute ? '' : root}/${path.trim()}`.split(/[\\/]+/)) {
+    if (part === '..') parts.pop();
+    else if (part && part !== '.') parts.push(part);
+  }
+  const rootParts = root.split(/[\\/]+/).filter(Boolean);
+  return parts.length > rootParts.length || parts.some((p, i) => p !== rootParts[i]);
+};
+
 const SyncForm: ForwardRefRenderFunction<FormProps, SyncFormProps> = ({ record, type }, ref) => {
   const isDisabled = type === 'edit';
   const [form] = Form.useForm();
@@ -48,6 +60,7 @@ const SyncForm: ForwardRefRenderFunction<FormProps, SyncFormProps> = ({ record,
       <Select disabled={isDisabled}>
         <Select.Option value="csv">.csv</Select.Option>
         <Select.Option value="json">.json</Select.Option>
+        <Select.Option value="dir">/ (dir)</Select.Option>
       </Select>
     </Form.Item>
   );
@@ -63,6 +76,21 @@ const SyncForm: ForwardRefRenderFunction<FormProps, SyncFormProps> = ({ record,
     </Tooltip>
   );
 
+  const dirTip = (
+    <Tooltip
+      title={<pre>{`one .md file per prompt:
+---
+cmd: review
+act: Code Reviewer
+tags: [code]
+enable: true
+---
+Review this {{language}} code…`}</pre>}
+    >
+      <a>Markdown directory</a>
+    </Tooltip>
+  );
+
   const csvTip = (
     <Tooltip
       title={<pre>{`"cmd","act","prompt"
@@ -91,7 +119,21 @@ const SyncForm: ForwardRefRenderFunction<FormProps, SyncFormProps> = ({ record,
         <Form.Item
           label="PATH"
           name="path"
-          rules={[{ required: true, message: 'Please input path!' }]}
+          dependencies={['protocol', 'ext']}
+          rules={[
+            { required: true, message: 'Please input path!' },
+            ({ getFieldValue }) => ({
+              validator: () => getFieldValue('ext') === 'dir' && getFieldValue('protocol') !== 'local'
+                ? Promise.reject(new Error('A directory of Markdown prompts has to be local'))
+                : Promise.resolve(),
+            }),
+            ({ getFieldValue }) => ({
+              // the app writes into the data directory on every sync, so watching it would never settle
+              validator: (_, value = '') => getFieldValue('ext') === 'dir' && !isWatchable(root, value)
+                ? Promise.reject(new Error('A directory of Markdown prompts cannot contain the data directory'))
+                : Promise.resolve(),
+            }),
+          ]}
           >
           <Input
             placeholder="YOUR_PATH"
@@ -100,10 +142,21 @@ const SyncForm: ForwardRefRenderFunction<FormProps, SyncFormProps> = ({ record,
             {...DISABLE_AUTO_COMPLETE}
           />
         </Form.Item>
+        <Form.Item
+          label="SHA-256"
+          name="sha256"
+          rules={[{ pattern: /^\s*[0-9a-fA-F]{64}\s*$/, message: 'A SHA-256 is 64 hex characters' }]}
+        >
+          <Input placeholder="Optional, pin the payload to this hash" {...DISABLE_AUTO_COMPLETE} />
+        </Form.Item>
+        <Form.Item label="Minisign" name="minisign_key">
+          <Input placeholder="Optional, public key checked against PATH.minisig" {...DISABLE_AUTO_COMPLETE} />
+        </Form.Item>
         <Form.Item style={{ display: 'none' }} name="id" initialValue={v4().replace(/-/g, '')}><input /></Form.Item>
       </Form>
       <div className="tip">
-        <p>The file supports only {csvTip} and {jsonTip} formats.</p>
+        <p>The file supports only {csvTip} and {jsonTip} formats, or a local {dirTip} that is re-read whenever a file in it changes.</p>
+        <p>With a SHA-256 or a minisign public key set, a payload that fails the check is refused and the last good copy is kept.</p>
       </div>
     </>
   )
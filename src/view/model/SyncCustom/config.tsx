@@ -1,5 +1,5 @@
 import { useState } from 'react';
-import { Tag, Space, Popconfirm } from 'antd';
+import { Tag, Space, Popconfirm, Tooltip } from 'antd';
 import { HistoryOutlined } from '@ant-design/icons';
 import * as shell from '@tauri-apps/plugin-shell';
 import * as path from '@tauri-apps/api/path';
@@ -41,6 +41,17 @@ export const syncColumns = () => [
       </div>
     ),
   },
+  {
+    title: 'Status',
+    dataIndex: 'last_status',
+    key: 'last_status',
+    width: 90,
+    render: (v: string, row: any) => v && (
+      <Tooltip title={row.last_error}>
+        <Tag color={{ ok: 'success', stale: 'warning', error: 'error', untrusted: 'error' }[v]}>{v}</Tag>
+      </Tooltip>
+    ),
+  },
   {
     title: 'Action',
     fixed: 'right',
@@ -82,6 +93,9 @@ const RenderPath = ({ row }: any) => {
 };
 
 export const getPath = async (row: any) => {
+  if (row.ext === 'dir') {
+    return await path.join(await chatRoot(), row.path);
+  }
   if (!/^http/.test(row.protocol)) {
     return await path.join(await chatRoot(), row.path) + `.${row.ext}`;
   } else {
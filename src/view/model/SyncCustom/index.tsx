@@ -2,22 +2,23 @@ import { useState, useRef, useEffect } from 'react';
 import { Table, Modal, Button, message } from 'antd';
 import { invoke } from '@tauri-apps/api/core';
 import * as path from '@tauri-apps/api/path';
-import { remove, readTextFile } from '@tauri-apps/plugin-fs';
+import { remove } from '@tauri-apps/plugin-fs';
 
 import useData from '@/hooks/useData';
 import useChatModel, { useCacheModel } from '@/hooks/useChatModel';
 import useColumns from '@/hooks/useColumns';
 import { TABLE_PAGINATION } from '@/hooks/useTable';
-import { CHAT_MODEL_JSON, chatRoot, readJSON, genCmd } from '@/utils';
-import { syncColumns, getPath } from './config';
+import { CHAT_MODEL_JSON, chatRoot } from '@/utils';
+import { syncColumns } from './config';
 import SyncForm from './Form';
+import { showParseWarnings, type ParseWarning } from '@/components/ParseWarnings';
 
-const fmtData = (data: Record<string, any>[] = []) => (Array.isArray(data) ? data : []).map((i) => ({ ...i, cmd: i.cmd ? i.cmd : genCmd(i.act), tags: ['user-sync'], enable: true }));
+type SourceStatus = { id: string; name: string; ok: boolean; count: number; from_cache: boolean; untrusted: boolean; error?: string; last_updated?: number; warnings: ParseWarning[] };
 
 export default function SyncCustom() {
   const [isVisible, setVisible] = useState(false);
   const { modelData, modelSet } = useChatModel('sync_custom', CHAT_MODEL_JSON);
-  const { modelCacheCmd, modelCacheSet } = useCacheModel();
+  const { modelCacheCmd } = useCacheModel();
   const { opData, opInit, opAdd, opRemove, opReplace, opSafeKey } = useData([]);
   const { columns, ...opInfo } = useColumns(syncColumns());
   const formRef = useRef<any>(null);
@@ -35,13 +36,16 @@ export default function SyncCustom() {
   useEffect(() => {
     if (!opInfo.opType) return;
     if (opInfo.opType === 'sync') {
-      const filename = `${opInfo?.opRecord?.id}.json`;
-      handleSync(filename).then((isOk: boolean) => {
+      handleSync().then((status) => {
         opInfo.resetRecord();
-        if (!isOk) return;
-        const data = opReplace(opInfo?.opRecord?.[opSafeKey], { ...opInfo?.opRecord, last_updated: Date.now() });
+        if (!status) return;
+        const data = opReplace(opInfo?.opRecord?.[opSafeKey], {
+          ...opInfo?.opRecord,
+          last_updated: status.last_updated ?? opInfo?.opRecord?.last_updated,
+          last_status: status.untrusted ? 'untrusted' : status.from_cache ? 'stale' : status.ok ? 'ok' : 'error',
+          last_error: status.error ?? null,
+        });
         modelSet(data);
-        opInfo.resetRecord();
       });
     }
     if (['edit', 'new'].includes(opInfo.opType)) {
@@ -61,38 +65,28 @@ export default function SyncCustom() {
     }
   }, [opInfo.opType, formRef]);
 
-  const handleSync = async (filename: string) => {
-    const record = opInfo?.opRecord;
-    const isJson = /json$/.test(record?.ext);
-    const file = await path.join(await chatRoot(), 'cache_model', filename);
-    const filePath = await getPath(record);
-
-    // https or http
-    if (/^http/.test(record?.protocol)) {
-      const data = await invoke('sync_user_prompts', { url: filePath, dataType: record?.ext });
-      if (data) {
-        await modelCacheSet(data as [], file);
+  // resolved, parsed and cached on the Rust side; see `sync_user_prompts`
+  const handleSync = async (): Promise<SourceStatus | undefined> => {
+    try {
+      const [status] = await invoke<SourceStatus[]>('sync_user_prompts', { ids: [opInfo?.opRecord?.id] });
+      if (status?.untrusted) {
+        message.error(`${status.name} was not synced: ${status.error}`);
+        if (status.ok) await modelCacheCmd();
+      } else if (status?.ok) {
         await modelCacheCmd();
-        message.success('ChatGPT Prompts data has been synchronized!');
-        return true;
+        if (status.from_cache) {
+          message.warning(`Sync failed, using the cached copy: ${status.error}`);
+        } else {
+          message.success(`ChatGPT Prompts data has been synchronized! (${status.count} prompts)`);
+        }
+        showParseWarnings(`${status.warnings.length} rows of ${status.name} need attention`, status.warnings);
       } else {
-        message.error('ChatGPT Prompts data sync failed, please try again!');
-        return false;
+        message.error(`ChatGPT Prompts data sync failed: ${status?.error}`);
       }
+      return status;
+    } catch (e) {
+      message.error(String(e));
     }
-    // local
-    if (isJson) {
-      // parse json
-      const data = await readJSON(filePath, { isRoot: true });
-      await modelCacheSet(fmtData(data), file);
-    } else {
-      // parse csv
-      const data = await readTextFile(filePath);
-      const list: Record<string, string>[] = await invoke('parse_prompt', { data });
-      await modelCacheSet(fmtData(list), file);
-    }
-    await modelCacheCmd();
-    return true;
   };
 
   const handleOk = () => {
@@ -1,5 +1,5 @@
 import { useEffect, useState } from 'react';
-import { Table, Button, Popconfirm } from 'antd';
+import { Table, Button, Popconfirm, message } from 'antd';
 import { invoke } from '@tauri-apps/api/core';
 import * as path from '@tauri-apps/api/path';
 import { openPath } from '@tauri-apps/plugin-opener';
@@ -9,12 +9,16 @@ import useData from '@/hooks/useData';
 import useColumns from '@/hooks/useColumns';
 import useChatModel, { useCacheModel } from '@/hooks/useChatModel';
 import { useTableRowSelection, TABLE_PAGINATION } from '@/hooks/useTable';
-import { fmtDate, chatRoot } from '@/utils';
+import { fmtDate, chatRoot, readJSON } from '@/utils';
+import { showParseWarnings, type ParseWarning } from '@/components/ParseWarnings';
+import SyncHistory from '@/components/SyncHistory';
 import { syncColumns } from './config';
 import './index.scss';
 
 const promptsURL = 'https://github.com/f/awesome-chatgpt-prompts/blob/main/prompts.csv';
 
+type SyncReport = { added: number; changed: number; removed: number; last_updated: number; data: any[]; warnings: ParseWarning[] };
+
 export default function SyncPrompts() {
   const { rowSelection, selectedRowIDs } = useTableRowSelection();
   const [jsonPath, setJsonPath] = useState('');
@@ -35,13 +39,15 @@ export default function SyncPrompts() {
   }, [modelCacheJson.length]);
 
   const handleSync = async () => {
-    const data = await invoke('sync_prompts', { time: Date.now() });
-    if (data) {
-      opInit(data as any[]);
-      modelSet({
-        id: 'chatgpt_prompts',
-        last_updated: Date.now(),
-      });
+    try {
+      const report = await invoke<SyncReport>('sync_prompts');
+      opInit(report.data);
+      // chat.model.json is already written, this only refreshes local state
+      modelSet({ id: 'chatgpt_prompts', last_updated: report.last_updated });
+      message.success(`Synced: ${report.added} added, ${report.changed} changed, ${report.removed} removed`);
+      showParseWarnings(`${report.warnings.length} rows of prompts.csv were skipped or repaired`, report.warnings);
+    } catch (e) {
+      message.error(String(e));
     }
   };
 
@@ -52,6 +58,12 @@ export default function SyncPrompts() {
     }
   }, [opInfo.opTime]);
 
+  const handleRollback = async () => {
+    opInit(await readJSON(jsonPath, { isRoot: true, isList: true }));
+    await invoke('window_reload', { label: 'core' });
+    await invoke('window_reload', { label: 'tray' });
+  };
+
   const handleEnable = (isEnable: boolean) => {
     const data = opReplaceItems(selectedRowIDs, { enable: isEnable })
     modelCacheSet(data);
@@ -70,6 +82,7 @@ export default function SyncPrompts() {
         >
           <Button type="primary">Sync</Button>
         </Popconfirm>
+        <SyncHistory sourceId="chatgpt_prompts" onRollback={handleRollback} />
         <div>
           {selectedItems.length > 0 && (
             <>
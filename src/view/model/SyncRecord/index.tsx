@@ -1,17 +1,19 @@
 import { useEffect, useState } from 'react';
 import { useLocation } from 'react-router-dom';
 import { ArrowLeftOutlined } from '@ant-design/icons';
-import { Table, Button } from 'antd';
+import { Table, Button, Alert } from 'antd';
+import { invoke } from '@tauri-apps/api/core';
 import * as shell from '@tauri-apps/plugin-shell';
 import * as path from '@tauri-apps/api/path';
 import useColumns from '@/hooks/useColumns';
 import useData from '@/hooks/useData';
 import { useCacheModel } from '@/hooks/useChatModel';
 import { useTableRowSelection, TABLE_PAGINATION } from '@/hooks/useTable';
-import { fmtDate, chatRoot } from '@/utils';
+import { fmtDate, chatRoot, readJSON } from '@/utils';
 import { getPath } from '@/view/model/SyncCustom/config';
 import { syncColumns } from './config';
 import useInit from '@/hooks/useInit';
+import SyncHistory from '@/components/SyncHistory';
 
 export default function SyncRecord() {
   const location = useLocation();
@@ -43,6 +45,12 @@ export default function SyncRecord() {
     }
   }, [opInfo.opTime]);
 
+  const handleRollback = async () => {
+    opInit(await readJSON(jsonPath, { isRoot: true, isList: true }));
+    await invoke('window_reload', { label: 'core' });
+    await invoke('window_reload', { label: 'tray' });
+  };
+
   const handleEnable = (isEnable: boolean) => {
     const data = opReplaceItems(selectedRowIDs, { enable: isEnable })
     modelCacheSet(data);
@@ -53,6 +61,7 @@ export default function SyncRecord() {
       <div className="chat-table-btns">
         <div>
           <Button shape="round" icon={<ArrowLeftOutlined />} onClick={() => history.back()} />
+          {state?.id && <SyncHistory sourceId={state.id} onRollback={handleRollback} />}
         </div>
         <div>
           {selectedItems.length > 0 && (
@@ -71,6 +80,15 @@ export default function SyncRecord() {
         </div>
         {state?.last_updated && <span style={{ marginLeft: 10, color: '#888', fontSize: 12 }}>Last updated on {fmtDate(state?.last_updated)}</span>}
       </div>
+      {['untrusted', 'error'].includes(state?.last_status) && (
+        <Alert
+          type="error"
+          showIcon
+          style={{ marginBottom: 10 }}
+          message={state.last_status === 'untrusted' ? 'Integrity check failed, showing the last good copy' : 'Last sync failed'}
+          description={state.last_error}
+        />
+      )}
       <Table
         key="prompt"
         rowKey="act"
@@ -1,6 +1,7 @@
 import { useEffect, ForwardRefRenderFunction, useImperativeHandle, forwardRef } from 'react';
 import { Form, Input, Switch } from 'antd';
 import type { FormProps } from 'antd';
+import { invoke } from '@tauri-apps/api/core';
 
 import Tags from '@comps/Tags';
 import { DISABLE_AUTO_COMPLETE } from '@/utils';
@@ -55,7 +56,11 @@ const UserCustomForm: ForwardRefRenderFunction<FormProps, UserCustomFormProps> =
       <Form.Item
         label="Prompt"
         name="prompt"
-        rules={[{ required: true, message: 'Please input prompt!' }]}
+        extra="Use {{name}}, {{name:long}}, {{name:number}} or {{name:a|b|c}}, optionally ending in =default, for values asked before inserting"
+        rules={[
+          { required: true, message: 'Please input prompt!' },
+          { validator: (_, value) => value ? invoke('prompt_vars', { prompt: value }).catch((e: any) => Promise.reject(new Error(e?.message ?? String(e)))) : Promise.resolve() },
+        ]}
       >
         <Input.TextArea rows={4} placeholder="Please input prompt" {...DISABLE_AUTO_COMPLETE} />
       </Form.Item>